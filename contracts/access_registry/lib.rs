@@ -4,6 +4,17 @@
 mod access_registry {
     use ink::storage::Mapping;
 
+    /// Cross-contract interface implemented by the configured `attribute_store`.
+    ///
+    /// `request_session` uses this to fetch the caller's Merkle root directly
+    /// from the store instead of trusting a caller-supplied value.
+    #[ink::trait_definition]
+    pub trait AttributeStore {
+        /// Return the current Merkle root published for `account`, if any.
+        #[ink(message)]
+        fn get_root(&self, account: Address) -> Option<[u8; 32]>;
+    }
+
     /// Defines entitlement levels for access control
     #[derive(Default, Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
     #[cfg_attr(
@@ -41,6 +52,12 @@ mod access_registry {
         pub is_revoked: bool,
         /// Block number when this session was created.
         pub created_at_block: u64,
+        /// Session this one was delegated from, if any.
+        pub parent_session_id: Option<[u8; 32]>,
+        /// Number of delegation hops from the root session (0 for a root session).
+        pub delegation_depth: u32,
+        /// The `scope_id`'s `ScopeRequirement::version` at issuance time.
+        pub scope_version: u32,
     }
 
     /// Merkle proof for an attribute.
@@ -73,6 +90,74 @@ mod access_registry {
         pub required_attributes: ink::prelude::vec::Vec<[u8; 32]>,
         /// Whether this scope is active
         pub active: bool,
+        /// Monotonically increasing version, bumped on every `set_scope_requirement`.
+        ///
+        /// Sessions record the scope's version at issuance time so rotating a
+        /// scope's requirements can auto-invalidate previously issued
+        /// sessions without revoking them individually.
+        pub version: u32,
+        /// Hash primitive the attribute tree for this scope was built with.
+        pub hash_algo: HashAlgo,
+        /// When `true`, [`Self::required_attributes`] proofs are verified in
+        /// sorted-pair (commutative) mode: each (current, sibling) pair is
+        /// lexicographically ordered before hashing and `proof_indices` is
+        /// ignored, matching the convention used by common off-chain Merkle
+        /// tree builders. When `false`, `proof_indices` determines each
+        /// pair's left/right order as before.
+        pub commutative: bool,
+    }
+
+    /// Hash primitive used to fold Merkle proof nodes for a scope.
+    ///
+    /// Lets the registry accept proofs from credential issuers that mint
+    /// trees with keccak256 (common in EVM tooling) as well as the
+    /// default Blake2x256.
+    #[derive(Default, Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum HashAlgo {
+        #[default]
+        Blake2x256,
+        Keccak256,
+    }
+
+    /// Lifecycle state of a [`PendingSessionRequest`].
+    #[derive(Default, Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RequestStatus {
+        #[default]
+        Pending,
+        Fulfilled,
+        Failed,
+    }
+
+    /// An async request for an off-chain key-generation node to mint a
+    /// session's ephemeral keypair and deliver the requester's key share.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct PendingSessionRequest {
+        /// Account that will own the resulting session.
+        pub requester: Address,
+        /// Resource scope the requested session will be issued for.
+        pub scope_id: [u8; 32],
+        /// Requested session duration, in blocks, once fulfilled.
+        pub duration_blocks: u64,
+        /// Block at which the request was submitted.
+        pub requested_at_block: u64,
+        /// Current lifecycle state.
+        pub status: RequestStatus,
+        /// Session ID of the grant created by `fulfill_session_request`.
+        pub session_id: Option<[u8; 32]>,
+        /// Encrypted key share for the requester to retrieve, once fulfilled.
+        pub encrypted_key_share: Option<ink::prelude::vec::Vec<u8>>,
     }
 
     /// Access registry contract for managing entitlements
@@ -82,12 +167,35 @@ mod access_registry {
         entitlements: Mapping<Address, EntitlementLevel>,
         /// Mapping from session ID to session grant
         sessions: Mapping<[u8; 32], SessionGrant>,
-        /// Contract owner who can grant/revoke entitlements
+        /// Contract owner who administers this registry
         owner: Address,
         /// Reference to `attribute_store` contract for Merkle root lookups
         attribute_store: Option<Address>,
         /// Scope requirements: `scope_id` -> required attribute hashes
         scope_requirements: Mapping<[u8; 32], ScopeRequirement>,
+        /// Mapping from a session ID to the child sessions delegated from it.
+        session_children: Mapping<[u8; 32], ink::prelude::vec::Vec<[u8; 32]>>,
+        /// Exclusive upper bound on a delegated session's `delegation_depth`.
+        max_delegation_depth: u32,
+        /// Mapping from request ID to pending/fulfilled/failed key-generation request.
+        pending_requests: Mapping<[u8; 32], PendingSessionRequest>,
+        /// Addresses of off-chain key-generation nodes allowed to fulfill requests.
+        generators: Mapping<Address, bool>,
+        /// Addresses (typically other contracts, e.g. `payment_integration`)
+        /// allowed to call [`Self::grant_entitlement`] / [`Self::revoke_entitlement`]
+        /// without holding full `owner` rights.
+        authorized_integrators: Mapping<Address, bool>,
+        /// Number of blocks a request may stay `Pending` before it can be cancelled.
+        request_timeout_blocks: u64,
+        /// Append-only log of leaf hashes for the aggregate session commitment.
+        ///
+        /// Indexed `0..session_leaf_count`. [`Self::sessions_root`] folds
+        /// this log into a single Merkle root on demand so an off-chain
+        /// light client can verify a session grant against one published
+        /// value instead of trusting a full node's storage reads.
+        session_leaves: Mapping<u32, [u8; 32]>,
+        /// Number of entries appended to `session_leaves`.
+        session_leaf_count: u32,
     }
 
     /// Events emitted by the contract
@@ -133,6 +241,58 @@ mod access_registry {
         scope_id: [u8; 32],
     }
 
+    #[ink(event)]
+    pub struct SessionProven {
+        #[ink(topic)]
+        session_id: [u8; 32],
+        #[ink(topic)]
+        caller: Address,
+    }
+
+    #[ink(event)]
+    pub struct SessionDelegated {
+        #[ink(topic)]
+        parent_session_id: [u8; 32],
+        #[ink(topic)]
+        child_session_id: [u8; 32],
+        delegation_depth: u32,
+    }
+
+    #[ink(event)]
+    pub struct KeyGenerationRequested {
+        #[ink(topic)]
+        request_id: [u8; 32],
+        #[ink(topic)]
+        requester: Address,
+        scope_id: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct KeyGenerationFulfilled {
+        #[ink(topic)]
+        request_id: [u8; 32],
+        #[ink(topic)]
+        session_id: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct KeyGenerationFailed {
+        #[ink(topic)]
+        request_id: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct GeneratorAuthorized {
+        #[ink(topic)]
+        generator: Address,
+    }
+
+    #[ink(event)]
+    pub struct IntegratorAuthorized {
+        #[ink(topic)]
+        integrator: Address,
+    }
+
     /// Errors that can occur during contract execution
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -155,6 +315,28 @@ mod access_registry {
         ScopeNotFound,
         /// Scope is inactive
         ScopeInactive,
+        /// Signature does not recover to the session's ephemeral public key
+        InvalidSignature,
+        /// Session has been revoked or its expiry block has passed
+        SessionExpired,
+        /// Child scope requires attributes not required by the parent scope
+        ScopeNotSubset,
+        /// Child session would expire after its parent
+        ChildExpiryExceedsParent,
+        /// Delegation chain would reach `max_delegation_depth`
+        DelegationDepthExceeded,
+        /// Key-generation request not found
+        RequestNotFound,
+        /// Request is not in the `Pending` state
+        RequestNotPending,
+        /// Caller is not a registered key-generation node
+        NotAuthorizedGenerator,
+        /// Caller is not a registered integrator
+        NotAuthorizedIntegrator,
+        /// Request has not yet passed `request_timeout_blocks`
+        RequestNotTimedOut,
+        /// Session's `scope_version` is older than the scope's current version
+        StaleScopeVersion,
     }
 
     pub type Result<T> = core::result::Result<T, Error>;
@@ -175,18 +357,33 @@ mod access_registry {
                 owner: Self::env().caller(),
                 attribute_store: None,
                 scope_requirements: Mapping::default(),
+                session_children: Mapping::default(),
+                max_delegation_depth: 8,
+                pending_requests: Mapping::default(),
+                generators: Mapping::default(),
+                authorized_integrators: Mapping::default(),
+                request_timeout_blocks: 100,
+                session_leaves: Mapping::default(),
+                session_leaf_count: 0,
             }
         }
 
-        /// Grant an entitlement to an account
+        /// Grant an entitlement to an account.
+        ///
+        /// Only the owner or an address registered via
+        /// [`Self::authorize_integrator`] may call this, so e.g. a
+        /// `payment_integration` deployment can be granted this narrow
+        /// capability without taking over `owner` and the rest of this
+        /// contract's admin surface.
         #[ink(message)]
         pub fn grant_entitlement(
             &mut self,
             account: Address,
             level: EntitlementLevel,
         ) -> Result<()> {
-            if self.env().caller() != self.owner {
-                return Err(Error::NotOwner);
+            let caller = self.env().caller();
+            if caller != self.owner && !self.is_integrator(caller) {
+                return Err(Error::NotAuthorizedIntegrator);
             }
 
             self.entitlements.insert(account, &level);
@@ -196,11 +393,15 @@ mod access_registry {
             Ok(())
         }
 
-        /// Revoke an entitlement from an account
+        /// Revoke an entitlement from an account.
+        ///
+        /// Only the owner or an address registered via
+        /// [`Self::authorize_integrator`] may call this.
         #[ink(message)]
         pub fn revoke_entitlement(&mut self, account: Address) -> Result<()> {
-            if self.env().caller() != self.owner {
-                return Err(Error::NotOwner);
+            let caller = self.env().caller();
+            if caller != self.owner && !self.is_integrator(caller) {
+                return Err(Error::NotAuthorizedIntegrator);
             }
 
             self.entitlements.remove(account);
@@ -260,6 +461,9 @@ mod access_registry {
                 expires_at_block,
                 is_revoked: false,
                 created_at_block: u64::from(self.env().block_number()),
+                parent_session_id: None,
+                delegation_depth: 0,
+                scope_version: self.current_scope_version(&scope_id),
             };
 
             self.sessions.insert(session_id, &grant);
@@ -278,176 +482,940 @@ mod access_registry {
             self.sessions.get(session_id)
         }
 
+        /// Current aggregate Merkle root over every session grant issued
+        /// through [`Self::request_session`], [`Self::request_session_multi`],
+        /// [`Self::delegate_session`], or [`Self::fulfill_session_request`]
+        /// (leaf = `H(session_id || scope_id || expires_at_block ||
+        /// subject)`), folded pairwise with the last node duplicated at odd
+        /// levels. An off-chain light client can cache this single value
+        /// and later check a grant against it via
+        /// [`Self::verify_session_inclusion`] instead of trusting a full
+        /// node's storage reads.
+        ///
+        /// [`Self::create_session`] is an owner-administered bypass with no
+        /// `subject` parameter to hash into a leaf, so sessions minted that
+        /// way are not covered by this root.
+        #[ink(message)]
+        pub fn sessions_root(&self) -> [u8; 32] {
+            self.compute_sessions_root()
+        }
+
+        /// Verify that a session grant is included in a given aggregate
+        /// `root` returned by [`Self::sessions_root`].
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn verify_session_inclusion(
+            &self,
+            session_id: [u8; 32],
+            scope_id: [u8; 32],
+            expires_at_block: u64,
+            subject: Address,
+            proof_path: ink::prelude::vec::Vec<[u8; 32]>,
+            proof_indices: ink::prelude::vec::Vec<u8>,
+            root: [u8; 32],
+        ) -> bool {
+            let leaf = Self::session_leaf_hash(&session_id, &scope_id, expires_at_block, &subject);
+            Self::verify_merkle_proof(
+                &leaf,
+                &proof_path,
+                &proof_indices,
+                &root,
+                HashAlgo::Blake2x256,
+                false,
+            )
+        }
+
+        /// Check whether a session is usable: it must exist, not be revoked,
+        /// not be past its expiry block, and its recorded `scope_version`
+        /// must not be older than the scope's current version.
+        ///
+        /// This lets the owner rotate a scope's attribute requirements via
+        /// `set_scope_requirement` and have every session issued for that
+        /// scope become invalid without revoking them one by one.
+        #[ink(message)]
+        pub fn is_session_valid(&self, session_id: [u8; 32]) -> bool {
+            match self.sessions.get(session_id) {
+                Some(grant) => {
+                    !grant.is_revoked
+                        && u64::from(self.env().block_number()) <= grant.expires_at_block
+                        && grant.scope_version >= self.current_scope_version(&grant.scope_id)
+                }
+                None => false,
+            }
+        }
+
         /// Revoke a session grant.
         ///
-        /// Only the contract owner can revoke sessions.
+        /// Only the contract owner can revoke sessions. Revocation cascades:
+        /// every session delegated from `session_id`, directly or
+        /// transitively, is revoked as well so a capability chain cannot
+        /// outlive the session it was derived from.
         #[ink(message)]
         pub fn revoke_session(&mut self, session_id: [u8; 32]) -> Result<()> {
             if self.env().caller() != self.owner {
                 return Err(Error::NotOwner);
             }
 
+            if self.sessions.get(session_id).is_none() {
+                return Err(Error::SessionNotFound);
+            }
+
+            self.revoke_session_and_descendants(session_id);
+
+            Ok(())
+        }
+
+        /// Mark `session_id` revoked and recurse into its delegated children.
+        fn revoke_session_and_descendants(&mut self, session_id: [u8; 32]) {
             if let Some(mut grant) = self.sessions.get(session_id) {
-                grant.is_revoked = true;
-                self.sessions.insert(session_id, &grant);
+                if !grant.is_revoked {
+                    grant.is_revoked = true;
+                    self.sessions.insert(session_id, &grant);
+                    self.env().emit_event(SessionRevoked { session_id });
+                }
+            }
 
-                self.env().emit_event(SessionRevoked { session_id });
+            if let Some(children) = self.session_children.get(session_id) {
+                for child_id in children {
+                    self.revoke_session_and_descendants(child_id);
+                }
+            }
+        }
 
-                Ok(())
-            } else {
-                Err(Error::SessionNotFound)
+        /// Prove possession of a session's ephemeral private key.
+        ///
+        /// The caller signs `challenge` (expected to be
+        /// `H(session_id || caller || block_number)`, see
+        /// [`Self::session_challenge`]) with the ephemeral private key and
+        /// submits the resulting recoverable ECDSA signature. The contract
+        /// recovers the public key from the signature and checks it matches
+        /// `grant.eph_pub_key` byte-for-byte. Binding the challenge to the
+        /// current block number prevents a captured signature from being
+        /// replayed in a later block.
+        #[ink(message)]
+        pub fn prove_session(
+            &mut self,
+            session_id: [u8; 32],
+            challenge: [u8; 32],
+            signature: [u8; 65],
+        ) -> Result<bool> {
+            let caller = self.env().caller();
+            let grant = self.sessions.get(session_id).ok_or(Error::SessionNotFound)?;
+
+            if grant.is_revoked || u64::from(self.env().block_number()) > grant.expires_at_block {
+                return Err(Error::SessionExpired);
+            }
+
+            if grant.scope_version < self.current_scope_version(&grant.scope_id) {
+                return Err(Error::StaleScopeVersion);
+            }
+
+            let expected_challenge = self.session_challenge(&session_id, &caller);
+            if challenge != expected_challenge {
+                return Err(Error::InvalidSignature);
+            }
+
+            let mut recovered = [0u8; 33];
+            ink::env::ecdsa_recover(&signature, &challenge, &mut recovered)
+                .map_err(|_| Error::InvalidSignature)?;
+
+            if recovered.as_slice() != grant.eph_pub_key.as_slice() {
+                return Err(Error::InvalidSignature);
             }
+
+            self.env().emit_event(SessionProven { session_id, caller });
+
+            Ok(true)
         }
 
-        /// Set the `attribute_store` contract address.
+        /// Compute the expected `prove_session` challenge for a session and caller.
+        ///
+        /// `H(session_id || caller || block_number)`, binding the challenge to
+        /// the current block so a signature cannot be replayed later.
+        fn session_challenge(&self, session_id: &[u8; 32], caller: &Address) -> [u8; 32] {
+            use ink::env::hash::{Blake2x256, HashOutput};
+
+            let mut input = ink::prelude::vec::Vec::new();
+            input.extend_from_slice(session_id);
+            input.extend_from_slice(caller.as_ref());
+            input.extend_from_slice(&self.env().block_number().to_le_bytes());
+
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
+            output
+        }
+
+        /// Delegate a narrower child session from a proven parent session.
+        ///
+        /// The caller proves control of `parent_session_id` via the same
+        /// ecdsa challenge/signature scheme as [`Self::prove_session`]. The
+        /// child session is only minted if:
+        /// - the parent session is not revoked or expired,
+        /// - the parent's recorded `scope_version` is not older than the
+        ///   scope's current version (same staleness check as
+        ///   [`Self::prove_session`]),
+        /// - the child scope's `required_attributes` are a subset of the
+        ///   parent scope's,
+        /// - `child_expires_at_block <= parent.expires_at_block`, and
+        /// - the resulting `delegation_depth` stays under
+        ///   `max_delegation_depth`.
+        ///
+        /// Revoking an ancestor session cascades to every session delegated
+        /// from it (see [`Self::revoke_session`]).
+        #[ink(message)]
+        pub fn delegate_session(
+            &mut self,
+            parent_session_id: [u8; 32],
+            child_eph_pub_key: ink::prelude::vec::Vec<u8>,
+            child_scope_id: [u8; 32],
+            child_duration_blocks: u64,
+            signature: [u8; 65],
+        ) -> Result<[u8; 32]> {
+            let caller = self.env().caller();
+            let parent = self
+                .sessions
+                .get(parent_session_id)
+                .ok_or(Error::SessionNotFound)?;
+
+            if parent.is_revoked || u64::from(self.env().block_number()) > parent.expires_at_block
+            {
+                return Err(Error::SessionExpired);
+            }
+
+            if parent.scope_version < self.current_scope_version(&parent.scope_id) {
+                return Err(Error::StaleScopeVersion);
+            }
+
+            let challenge = self.session_challenge(&parent_session_id, &caller);
+            let mut recovered = [0u8; 33];
+            ink::env::ecdsa_recover(&signature, &challenge, &mut recovered)
+                .map_err(|_| Error::InvalidSignature)?;
+            if recovered.as_slice() != parent.eph_pub_key.as_slice() {
+                return Err(Error::InvalidSignature);
+            }
+
+            if parent.delegation_depth + 1 >= self.max_delegation_depth {
+                return Err(Error::DelegationDepthExceeded);
+            }
+
+            let parent_requirement = self
+                .scope_requirements
+                .get(parent.scope_id)
+                .ok_or(Error::ScopeNotFound)?;
+            let child_requirement = self
+                .scope_requirements
+                .get(child_scope_id)
+                .ok_or(Error::ScopeNotFound)?;
+            let is_subset = child_requirement
+                .required_attributes
+                .iter()
+                .all(|attr| parent_requirement.required_attributes.contains(attr));
+            if !is_subset {
+                return Err(Error::ScopeNotSubset);
+            }
+
+            let child_expires_at_block = u64::from(self.env().block_number()) + child_duration_blocks;
+            if child_expires_at_block > parent.expires_at_block {
+                return Err(Error::ChildExpiryExceedsParent);
+            }
+
+            // `session_children` is append-only (a revoke never removes an
+            // entry), so its current length is a per-parent nonce that's
+            // distinct for every delegation from this parent, even a second
+            // one after the first child was revoked.
+            let mut children = self
+                .session_children
+                .get(parent_session_id)
+                .unwrap_or_default();
+            let nonce = children.len() as u64;
+
+            let child_session_id =
+                self.compute_child_session_id(&parent_session_id, &caller, &child_scope_id, nonce);
+            let delegation_depth = parent.delegation_depth + 1;
+
+            let child_grant = SessionGrant {
+                eph_pub_key: child_eph_pub_key,
+                scope_id: child_scope_id,
+                expires_at_block: child_expires_at_block,
+                is_revoked: false,
+                created_at_block: u64::from(self.env().block_number()),
+                parent_session_id: Some(parent_session_id),
+                delegation_depth,
+                scope_version: self.current_scope_version(&child_scope_id),
+            };
+            self.sessions.insert(child_session_id, &child_grant);
+            self.append_session_leaf(&child_session_id, &child_scope_id, child_expires_at_block, &caller);
+
+            children.push(child_session_id);
+            self.session_children.insert(parent_session_id, &children);
+
+            self.env().emit_event(SessionDelegated {
+                parent_session_id,
+                child_session_id,
+                delegation_depth,
+            });
+
+            Ok(child_session_id)
+        }
+
+        /// Set the exclusive upper bound on a delegated session's `delegation_depth`.
         ///
         /// Only the contract owner can configure this.
         #[ink(message)]
-        pub fn set_attribute_store(&mut self, address: Address) -> Result<()> {
+        pub fn set_max_delegation_depth(&mut self, max_delegation_depth: u32) -> Result<()> {
             if self.env().caller() != self.owner {
                 return Err(Error::NotOwner);
             }
-            self.attribute_store = Some(address);
+            self.max_delegation_depth = max_delegation_depth;
             Ok(())
         }
 
-        /// Get the `attribute_store` contract address.
+        /// Get the exclusive upper bound on a delegated session's `delegation_depth`.
         #[ink(message)]
-        pub fn get_attribute_store(&self) -> Option<Address> {
-            self.attribute_store
+        pub fn get_max_delegation_depth(&self) -> u32 {
+            self.max_delegation_depth
         }
 
-        /// Set scope requirements.
+        /// Authorize an address as an off-chain key-generation node.
         ///
-        /// Only the contract owner can define scope requirements.
+        /// Only the contract owner can configure this.
         #[ink(message)]
-        pub fn set_scope_requirement(
-            &mut self,
-            scope_id: [u8; 32],
-            required_attributes: ink::prelude::vec::Vec<[u8; 32]>,
-            active: bool,
-        ) -> Result<()> {
+        pub fn authorize_generator(&mut self, generator: Address) -> Result<()> {
             if self.env().caller() != self.owner {
                 return Err(Error::NotOwner);
             }
+            self.generators.insert(generator, &true);
+            self.env().emit_event(GeneratorAuthorized { generator });
+            Ok(())
+        }
 
-            let requirement = ScopeRequirement {
-                required_attributes,
-                active,
-            };
-            self.scope_requirements.insert(scope_id, &requirement);
-
-            self.env().emit_event(ScopeRequirementSet { scope_id });
+        /// Check whether an address is an authorized key-generation node.
+        #[ink(message)]
+        pub fn is_generator(&self, account: Address) -> bool {
+            self.generators.get(account).unwrap_or(false)
+        }
 
+        /// Authorize an address to call [`Self::grant_entitlement`] /
+        /// [`Self::revoke_entitlement`] without holding full `owner` rights.
+        ///
+        /// Only the contract owner can configure this.
+        #[ink(message)]
+        pub fn authorize_integrator(&mut self, integrator: Address) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.authorized_integrators.insert(integrator, &true);
+            self.env().emit_event(IntegratorAuthorized { integrator });
             Ok(())
         }
 
-        /// Get scope requirements.
+        /// Check whether an address is an authorized integrator.
         #[ink(message)]
-        pub fn get_scope_requirement(&self, scope_id: [u8; 32]) -> Option<ScopeRequirement> {
-            self.scope_requirements.get(scope_id)
+        pub fn is_integrator(&self, account: Address) -> bool {
+            self.authorized_integrators.get(account).unwrap_or(false)
         }
 
-        /// Request a session by proving attributes via Merkle proofs.
+        /// Set how many blocks a `Pending` request may age before it can be cancelled.
         ///
-        /// The caller provides their attribute root and proofs. The contract:
-        /// 1. Verifies `attribute_store` is configured
-        /// 2. Validates each proof against the provided root
-        /// 3. Checks all required attributes for the scope are proven
-        /// 4. Creates and returns the session
+        /// Only the contract owner can configure this.
+        #[ink(message)]
+        pub fn set_request_timeout_blocks(&mut self, request_timeout_blocks: u64) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.request_timeout_blocks = request_timeout_blocks;
+            Ok(())
+        }
+
+        /// Request asynchronous generation of a session's ephemeral keypair.
         ///
-        /// Note: In a full implementation, the root would be fetched via
-        /// cross-contract call to `attribute_store.get_root(caller)`.
+        /// Records a [`PendingSessionRequest`] and emits
+        /// `KeyGenerationRequested` for off-chain key-generation nodes to
+        /// observe. A registered generator later calls
+        /// [`Self::fulfill_session_request`] (or the request times out and
+        /// is cancelled via [`Self::cancel_timed_out_request`]).
         #[ink(message)]
-        #[allow(clippy::needless_pass_by_value)]
-        pub fn request_session(
+        pub fn request_key_generation(
             &mut self,
-            eph_pub_key: ink::prelude::vec::Vec<u8>,
             scope_id: [u8; 32],
             duration_blocks: u64,
-            proofs: ink::prelude::vec::Vec<AttributeProof>,
-            root: [u8; 32],
         ) -> Result<[u8; 32]> {
-            let caller = self.env().caller();
+            let requester = self.env().caller();
+            let requested_at_block = u64::from(self.env().block_number());
+            let request_id = self.compute_request_id(&requester, &scope_id, requested_at_block);
 
-            // Verify attribute_store is configured
-            let _attribute_store = self
-                .attribute_store
-                .ok_or(Error::AttributeStoreNotConfigured)?;
+            let request = PendingSessionRequest {
+                requester,
+                scope_id,
+                duration_blocks,
+                requested_at_block,
+                status: RequestStatus::Pending,
+                session_id: None,
+                encrypted_key_share: None,
+            };
+            self.pending_requests.insert(request_id, &request);
 
-            // TODO: Cross-contract call to attribute_store.get_root(caller)
-            // For now, we accept the root parameter
-            // In production: verify root matches stored root
+            self.env().emit_event(KeyGenerationRequested {
+                request_id,
+                requester,
+                scope_id,
+            });
 
-            // Get scope requirements
-            let requirement = self
-                .scope_requirements
-                .get(scope_id)
-                .ok_or(Error::ScopeNotFound)?;
+            Ok(request_id)
+        }
 
-            if !requirement.active {
-                return Err(Error::ScopeInactive);
+        /// Fulfill a pending key-generation request, minting the session grant.
+        ///
+        /// Only an address registered via [`Self::authorize_generator`] may
+        /// call this. Stores `encrypted_key_share` for the original requester
+        /// to retrieve via [`Self::get_pending_request`].
+        #[ink(message)]
+        pub fn fulfill_session_request(
+            &mut self,
+            request_id: [u8; 32],
+            eph_pub_key: ink::prelude::vec::Vec<u8>,
+            encrypted_key_share: ink::prelude::vec::Vec<u8>,
+        ) -> Result<[u8; 32]> {
+            if !self.is_generator(self.env().caller()) {
+                return Err(Error::NotAuthorizedGenerator);
             }
 
-            // Verify each required attribute has a valid proof
-            for required_hash in &requirement.required_attributes {
-                let proof = proofs
-                    .iter()
-                    .find(|p| &p.attribute_hash == required_hash)
-                    .ok_or(Error::MissingRequiredAttribute)?;
+            let mut request = self
+                .pending_requests
+                .get(request_id)
+                .ok_or(Error::RequestNotFound)?;
 
-                if !Self::verify_merkle_proof(
-                    &proof.attribute_hash,
-                    &proof.proof_path,
-                    &proof.proof_indices,
-                    &root,
-                ) {
-                    return Err(Error::InvalidProof);
-                }
+            if request.status != RequestStatus::Pending {
+                return Err(Error::RequestNotPending);
             }
 
-            // Generate session ID from caller + scope + block
-            let session_id = self.compute_session_id(&caller, &scope_id);
-
-            let expires_at_block = u64::from(self.env().block_number()) + duration_blocks;
+            let session_id =
+                self.compute_session_id(&request.requester, &request.scope_id);
+            let expires_at_block =
+                u64::from(self.env().block_number()) + request.duration_blocks;
 
             let grant = SessionGrant {
                 eph_pub_key,
-                scope_id,
+                scope_id: request.scope_id,
                 expires_at_block,
                 is_revoked: false,
                 created_at_block: u64::from(self.env().block_number()),
+                parent_session_id: None,
+                delegation_depth: 0,
+                scope_version: self.current_scope_version(&request.scope_id),
             };
-
             self.sessions.insert(session_id, &grant);
+            self.append_session_leaf(&session_id, &request.scope_id, expires_at_block, &request.requester);
 
-            self.env().emit_event(SessionRequested {
+            request.status = RequestStatus::Fulfilled;
+            request.session_id = Some(session_id);
+            request.encrypted_key_share = Some(encrypted_key_share);
+            self.pending_requests.insert(request_id, &request);
+
+            self.env().emit_event(KeyGenerationFulfilled {
+                request_id,
+                session_id,
+            });
+
+            Ok(session_id)
+        }
+
+        /// Cancel a `Pending` request once it has aged past `request_timeout_blocks`.
+        #[ink(message)]
+        pub fn cancel_timed_out_request(&mut self, request_id: [u8; 32]) -> Result<()> {
+            let mut request = self
+                .pending_requests
+                .get(request_id)
+                .ok_or(Error::RequestNotFound)?;
+
+            if request.status != RequestStatus::Pending {
+                return Err(Error::RequestNotPending);
+            }
+
+            let now = u64::from(self.env().block_number());
+            if now < request.requested_at_block + self.request_timeout_blocks {
+                return Err(Error::RequestNotTimedOut);
+            }
+
+            request.status = RequestStatus::Failed;
+            self.pending_requests.insert(request_id, &request);
+
+            self.env().emit_event(KeyGenerationFailed { request_id });
+
+            Ok(())
+        }
+
+        /// Get a pending/fulfilled/failed key-generation request by ID.
+        #[ink(message)]
+        pub fn get_pending_request(&self, request_id: [u8; 32]) -> Option<PendingSessionRequest> {
+            self.pending_requests.get(request_id)
+        }
+
+        /// Derive a request ID from requester, scope, and block number.
+        fn compute_request_id(
+            &self,
+            requester: &Address,
+            scope_id: &[u8; 32],
+            block_number: u64,
+        ) -> [u8; 32] {
+            use ink::env::hash::{Blake2x256, HashOutput};
+
+            let mut input = ink::prelude::vec::Vec::new();
+            input.extend_from_slice(b"key-generation-request");
+            input.extend_from_slice(requester.as_ref());
+            input.extend_from_slice(scope_id);
+            input.extend_from_slice(&block_number.to_le_bytes());
+
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
+            output
+        }
+
+        /// Set the `attribute_store` contract address.
+        ///
+        /// Only the contract owner can configure this.
+        #[ink(message)]
+        pub fn set_attribute_store(&mut self, address: Address) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.attribute_store = Some(address);
+            Ok(())
+        }
+
+        /// Get the `attribute_store` contract address.
+        #[ink(message)]
+        pub fn get_attribute_store(&self) -> Option<Address> {
+            self.attribute_store
+        }
+
+        /// Set scope requirements.
+        ///
+        /// Only the contract owner can define scope requirements.
+        #[ink(message)]
+        pub fn set_scope_requirement(
+            &mut self,
+            scope_id: [u8; 32],
+            required_attributes: ink::prelude::vec::Vec<[u8; 32]>,
+            active: bool,
+            hash_algo: HashAlgo,
+            commutative: bool,
+        ) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            let version = self
+                .scope_requirements
+                .get(scope_id)
+                .map_or(1, |existing| existing.version + 1);
+
+            let requirement = ScopeRequirement {
+                required_attributes,
+                active,
+                version,
+                hash_algo,
+                commutative,
+            };
+            self.scope_requirements.insert(scope_id, &requirement);
+
+            self.env().emit_event(ScopeRequirementSet { scope_id });
+
+            Ok(())
+        }
+
+        /// Get scope requirements.
+        #[ink(message)]
+        pub fn get_scope_requirement(&self, scope_id: [u8; 32]) -> Option<ScopeRequirement> {
+            self.scope_requirements.get(scope_id)
+        }
+
+        /// Record a `proof_path`/`proof_indices` pair for `leaf_index` within
+        /// `leaves`, using the exact tree-building convention
+        /// [`Self::verify_merkle_proof`] expects: pairwise adjacent nodes,
+        /// the last node duplicated when a level has an odd count, and pairs
+        /// hashed in sorted order when `commutative` is set or in
+        /// left/right order otherwise.
+        ///
+        /// Integrators who only know their full, ordered set of attribute
+        /// leaves (not the contract's internal hashing/ordering rules) can
+        /// call this instead of reimplementing the tree themselves, so a
+        /// proof built here is guaranteed to verify against the root those
+        /// same `leaves` produce. Returns `None` if `leaves` is empty or
+        /// `leaf_index` is out of range.
+        ///
+        /// Only covers the single-attribute [`Self::request_session`] path;
+        /// generating a combined multiproof for
+        /// [`Self::request_session_multi`] requires the full standard
+        /// multiproof construction algorithm and isn't implemented here.
+        #[ink(message)]
+        pub fn record_merkle_proof(
+            &self,
+            leaves: ink::prelude::vec::Vec<[u8; 32]>,
+            leaf_index: u32,
+            hash_algo: HashAlgo,
+            commutative: bool,
+        ) -> Option<(
+            ink::prelude::vec::Vec<[u8; 32]>,
+            ink::prelude::vec::Vec<u8>,
+            [u8; 32],
+        )> {
+            if leaves.is_empty() || leaf_index as usize >= leaves.len() {
+                return None;
+            }
+
+            let levels = Self::build_merkle_levels(&leaves, hash_algo, commutative);
+
+            let mut proof_path = ink::prelude::vec::Vec::new();
+            let mut proof_indices = ink::prelude::vec::Vec::new();
+            let mut index = leaf_index as usize;
+
+            for level in &levels[..levels.len() - 1] {
+                let is_left = index % 2 == 0;
+                let sibling_index = if is_left { index + 1 } else { index - 1 };
+                let sibling = if sibling_index < level.len() {
+                    level[sibling_index]
+                } else {
+                    level[index]
+                };
+
+                proof_path.push(sibling);
+                proof_indices.push(if is_left { 0u8 } else { 1u8 });
+                index /= 2;
+            }
+
+            let root = levels[levels.len() - 1][0];
+            Some((proof_path, proof_indices, root))
+        }
+
+        /// Build every level of a pairwise Merkle tree over `leaves`, from
+        /// the leaves themselves (level 0) up to the single-element root
+        /// level, duplicating the last node at each odd-length level.
+        fn build_merkle_levels(
+            leaves: &[[u8; 32]],
+            hash_algo: HashAlgo,
+            commutative: bool,
+        ) -> ink::prelude::vec::Vec<ink::prelude::vec::Vec<[u8; 32]>> {
+            let mut levels = ink::prelude::vec::Vec::new();
+            levels.push(leaves.to_vec());
+
+            while levels[levels.len() - 1].len() > 1 {
+                let current = &levels[levels.len() - 1];
+                let mut next = ink::prelude::vec::Vec::with_capacity(current.len().div_ceil(2));
+                let mut i = 0;
+                while i < current.len() {
+                    let left = current[i];
+                    let right = if i + 1 < current.len() { current[i + 1] } else { current[i] };
+
+                    let hash = if commutative {
+                        Self::hash_pair_sorted(&left, &right, hash_algo)
+                    } else {
+                        let mut input = [0u8; 64];
+                        input[..32].copy_from_slice(&left);
+                        input[32..].copy_from_slice(&right);
+                        Self::hash_bytes64(&input, hash_algo)
+                    };
+
+                    next.push(hash);
+                    i += 2;
+                }
+                levels.push(next);
+            }
+
+            levels
+        }
+
+        /// Request a session by proving attributes via Merkle proofs.
+        ///
+        /// The contract:
+        /// 1. Verifies `attribute_store` is configured
+        /// 2. Fetches the caller's Merkle root via a cross-contract call to
+        ///    `attribute_store.get_root(caller)`
+        /// 3. Validates each proof against that root
+        /// 4. Checks all required attributes for the scope are proven
+        /// 5. Creates and returns the session
+        #[ink(message)]
+        #[allow(clippy::needless_pass_by_value)]
+        pub fn request_session(
+            &mut self,
+            eph_pub_key: ink::prelude::vec::Vec<u8>,
+            scope_id: [u8; 32],
+            duration_blocks: u64,
+            proofs: ink::prelude::vec::Vec<AttributeProof>,
+        ) -> Result<[u8; 32]> {
+            let caller = self.env().caller();
+
+            let attribute_store_addr = self
+                .attribute_store
+                .ok_or(Error::AttributeStoreNotConfigured)?;
+
+            let store: ink::contract_ref!(AttributeStore) = attribute_store_addr.into();
+            let root = store.get_root(caller).ok_or(Error::RootNotFound)?;
+
+            self.request_session_with_root(caller, eph_pub_key, scope_id, duration_blocks, proofs, root)
+        }
+
+        /// Core of [`Self::request_session`], parameterized over an already
+        /// resolved `root` so the Merkle-proof logic can be exercised without
+        /// a live `attribute_store` cross-contract call (e.g. in unit tests).
+        fn request_session_with_root(
+            &mut self,
+            caller: Address,
+            eph_pub_key: ink::prelude::vec::Vec<u8>,
+            scope_id: [u8; 32],
+            duration_blocks: u64,
+            proofs: ink::prelude::vec::Vec<AttributeProof>,
+            root: [u8; 32],
+        ) -> Result<[u8; 32]> {
+            // Get scope requirements
+            let requirement = self
+                .scope_requirements
+                .get(scope_id)
+                .ok_or(Error::ScopeNotFound)?;
+
+            if !requirement.active {
+                return Err(Error::ScopeInactive);
+            }
+
+            // Verify each required attribute has a valid proof
+            for required_hash in &requirement.required_attributes {
+                let proof = proofs
+                    .iter()
+                    .find(|p| &p.attribute_hash == required_hash)
+                    .ok_or(Error::MissingRequiredAttribute)?;
+
+                if !Self::verify_merkle_proof(
+                    &proof.attribute_hash,
+                    &proof.proof_path,
+                    &proof.proof_indices,
+                    &root,
+                    requirement.hash_algo,
+                    requirement.commutative,
+                ) {
+                    return Err(Error::InvalidProof);
+                }
+            }
+
+            Ok(self.issue_session(caller, eph_pub_key, scope_id, duration_blocks))
+        }
+
+        /// Request a session by proving all of a scope's required attributes
+        /// with a single combined Merkle multiproof, instead of one
+        /// independent [`AttributeProof`] per attribute.
+        ///
+        /// `leaves` must contain at least every hash in the scope's
+        /// `required_attributes`, in the canonical order the attribute tree
+        /// was built in. See [`Self::verify_merkle_multiproof`] for the
+        /// verification algorithm.
+        #[ink(message)]
+        #[allow(clippy::needless_pass_by_value)]
+        pub fn request_session_multi(
+            &mut self,
+            eph_pub_key: ink::prelude::vec::Vec<u8>,
+            scope_id: [u8; 32],
+            duration_blocks: u64,
+            leaves: ink::prelude::vec::Vec<[u8; 32]>,
+            proof: ink::prelude::vec::Vec<[u8; 32]>,
+            proof_flags: ink::prelude::vec::Vec<bool>,
+        ) -> Result<[u8; 32]> {
+            let caller = self.env().caller();
+
+            let attribute_store_addr = self
+                .attribute_store
+                .ok_or(Error::AttributeStoreNotConfigured)?;
+
+            let store: ink::contract_ref!(AttributeStore) = attribute_store_addr.into();
+            let root = store.get_root(caller).ok_or(Error::RootNotFound)?;
+
+            self.request_session_multi_with_root(
+                caller,
+                eph_pub_key,
+                scope_id,
+                duration_blocks,
+                leaves,
+                proof,
+                proof_flags,
+                root,
+            )
+        }
+
+        /// Core of [`Self::request_session_multi`], parameterized over an
+        /// already resolved `root` so the multiproof logic can be exercised
+        /// without a live `attribute_store` cross-contract call (e.g. in
+        /// unit tests).
+        fn request_session_multi_with_root(
+            &mut self,
+            caller: Address,
+            eph_pub_key: ink::prelude::vec::Vec<u8>,
+            scope_id: [u8; 32],
+            duration_blocks: u64,
+            leaves: ink::prelude::vec::Vec<[u8; 32]>,
+            proof: ink::prelude::vec::Vec<[u8; 32]>,
+            proof_flags: ink::prelude::vec::Vec<bool>,
+            root: [u8; 32],
+        ) -> Result<[u8; 32]> {
+            let requirement = self
+                .scope_requirements
+                .get(scope_id)
+                .ok_or(Error::ScopeNotFound)?;
+
+            if !requirement.active {
+                return Err(Error::ScopeInactive);
+            }
+
+            for required_hash in &requirement.required_attributes {
+                if !leaves.contains(required_hash) {
+                    return Err(Error::MissingRequiredAttribute);
+                }
+            }
+
+            if !Self::verify_merkle_multiproof(&leaves, &proof, &proof_flags, &root, requirement.hash_algo)
+            {
+                return Err(Error::InvalidProof);
+            }
+
+            Ok(self.issue_session(caller, eph_pub_key, scope_id, duration_blocks))
+        }
+
+        /// Create and store a `SessionGrant` for `caller` and emit `SessionRequested`.
+        ///
+        /// Shared tail of [`Self::request_session_with_root`] and
+        /// [`Self::request_session_multi_with_root`] once a caller's proof(s) have
+        /// already been verified.
+        fn issue_session(
+            &mut self,
+            caller: Address,
+            eph_pub_key: ink::prelude::vec::Vec<u8>,
+            scope_id: [u8; 32],
+            duration_blocks: u64,
+        ) -> [u8; 32] {
+            let session_id = self.compute_session_id(&caller, &scope_id);
+            let expires_at_block = u64::from(self.env().block_number()) + duration_blocks;
+
+            let grant = SessionGrant {
+                eph_pub_key,
+                scope_id,
+                expires_at_block,
+                is_revoked: false,
+                created_at_block: u64::from(self.env().block_number()),
+                parent_session_id: None,
+                delegation_depth: 0,
+                scope_version: self.current_scope_version(&scope_id),
+            };
+
+            self.sessions.insert(session_id, &grant);
+            self.append_session_leaf(&session_id, &scope_id, expires_at_block, &caller);
+
+            self.env().emit_event(SessionRequested {
                 session_id,
                 requester: caller,
                 scope_id,
                 expires_at_block,
             });
 
-            Ok(session_id)
+            session_id
+        }
+
+        /// Append the leaf `H(session_id || scope_id || expires_at_block ||
+        /// subject)` for a freshly issued session to the aggregate commitment log.
+        fn append_session_leaf(
+            &mut self,
+            session_id: &[u8; 32],
+            scope_id: &[u8; 32],
+            expires_at_block: u64,
+            subject: &Address,
+        ) {
+            let leaf = Self::session_leaf_hash(session_id, scope_id, expires_at_block, subject);
+            let index = self.session_leaf_count;
+            self.session_leaves.insert(index, &leaf);
+            self.session_leaf_count = index + 1;
+        }
+
+        /// Compute the leaf hash for a session grant: `H(session_id ||
+        /// scope_id || expires_at_block || subject)`.
+        fn session_leaf_hash(
+            session_id: &[u8; 32],
+            scope_id: &[u8; 32],
+            expires_at_block: u64,
+            subject: &Address,
+        ) -> [u8; 32] {
+            use ink::env::hash::{Blake2x256, HashOutput};
+
+            let mut input = ink::prelude::vec::Vec::new();
+            input.extend_from_slice(session_id);
+            input.extend_from_slice(scope_id);
+            input.extend_from_slice(&expires_at_block.to_le_bytes());
+            input.extend_from_slice(subject.as_ref());
+
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
+            output
+        }
+
+        /// Fold the append-only `session_leaves` log into a single Merkle
+        /// root, duplicating the last node at each level when the current
+        /// level has an odd number of nodes.
+        ///
+        /// Rebuilt from scratch on every call rather than maintained
+        /// incrementally: `request_session` already pays O(1) storage
+        /// writes per session, and light clients only need a correct root
+        /// at query time, not the cheapest possible write path.
+        fn compute_sessions_root(&self) -> [u8; 32] {
+            let count = self.session_leaf_count;
+            if count == 0 {
+                return [0u8; 32];
+            }
+
+            let mut level: ink::prelude::vec::Vec<[u8; 32]> = (0..count)
+                .map(|i| self.session_leaves.get(i).unwrap_or([0u8; 32]))
+                .collect();
+
+            while level.len() > 1 {
+                let mut next = ink::prelude::vec::Vec::with_capacity(level.len().div_ceil(2));
+                let mut i = 0;
+                while i < level.len() {
+                    let left = level[i];
+                    let right = if i + 1 < level.len() { level[i + 1] } else { level[i] };
+                    let mut input = [0u8; 64];
+                    input[..32].copy_from_slice(&left);
+                    input[32..].copy_from_slice(&right);
+                    next.push(Self::hash_bytes64(&input, HashAlgo::Blake2x256));
+                    i += 2;
+                }
+                level = next;
+            }
+
+            level[0]
         }
 
         /// Verify a Merkle proof.
         ///
-        /// Returns true if the proof path from leaf to root is valid.
+        /// Returns true if the proof path from leaf to root is valid. When
+        /// `commutative` is `true`, each (current, sibling) pair is
+        /// lexicographically ordered before hashing and `proof_indices` is
+        /// ignored entirely (the caller may pass an empty vector); otherwise
+        /// `proof_indices` selects the left/right order at each level.
         fn verify_merkle_proof(
             leaf: &[u8; 32],
             proof_path: &[[u8; 32]],
             proof_indices: &[u8],
             root: &[u8; 32],
+            hash_algo: HashAlgo,
+            commutative: bool,
         ) -> bool {
-            use ink::env::hash::{Blake2x256, HashOutput};
-
-            if proof_path.len() != proof_indices.len() {
+            if !commutative && proof_path.len() != proof_indices.len() {
                 return false;
             }
 
             let mut current = *leaf;
 
+            if commutative {
+                for sibling in proof_path {
+                    current = Self::hash_pair_sorted(&current, sibling, hash_algo);
+                }
+                return current == *root;
+            }
+
             for (sibling, &index) in proof_path.iter().zip(proof_indices.iter()) {
                 let mut input = [0u8; 64];
                 if index == 0 {
@@ -460,14 +1428,147 @@ mod access_registry {
                     input[32..].copy_from_slice(&current);
                 }
 
-                let mut output = <Blake2x256 as HashOutput>::Type::default();
-                ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
-                current = output;
+                current = Self::hash_bytes64(&input, hash_algo);
             }
 
             current == *root
         }
 
+        /// Hash a 64-byte pair with the given [`HashAlgo`].
+        fn hash_bytes64(input: &[u8; 64], hash_algo: HashAlgo) -> [u8; 32] {
+            use ink::env::hash::{Blake2x256, HashOutput, Keccak256};
+
+            match hash_algo {
+                HashAlgo::Blake2x256 => {
+                    let mut output = <Blake2x256 as HashOutput>::Type::default();
+                    ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+                    output
+                }
+                HashAlgo::Keccak256 => {
+                    let mut output = <Keccak256 as HashOutput>::Type::default();
+                    ink::env::hash_bytes::<Keccak256>(input, &mut output);
+                    output
+                }
+            }
+        }
+
+        /// Verify a flag-driven Merkle multiproof covering several leaves at once.
+        ///
+        /// `leaves` is the sorted set of claimed attribute hashes, `proof` is
+        /// the flat vector of additional sibling hashes not derivable from
+        /// `leaves`, and `proof_flags` (length `leaves.len() + proof.len() -
+        /// 1`) drives the fold: at each step, the first combine operand is
+        /// always popped from the leaf/already-computed-hash stream; a `true`
+        /// flag pops the second operand from that same stream, a `false`
+        /// flag takes the next entry from `proof` instead. Each pair is
+        /// hashed in sorted byte order with `hash_algo` (matching the
+        /// commutative convention so no direction bits are needed), and the
+        /// final remaining hash must equal `root` with every leaf and proof
+        /// entry consumed exactly once.
+        fn verify_merkle_multiproof(
+            leaves: &[[u8; 32]],
+            proof: &[[u8; 32]],
+            proof_flags: &[bool],
+            root: &[u8; 32],
+            hash_algo: HashAlgo,
+        ) -> bool {
+            let total_hashes = proof_flags.len();
+            if leaves.is_empty() && proof.is_empty() {
+                return false;
+            }
+            if total_hashes + 1 != leaves.len() + proof.len() {
+                return false;
+            }
+
+            let mut hashes = ink::prelude::vec::Vec::with_capacity(total_hashes);
+            let mut leaf_pos = 0usize;
+            let mut hash_pos = 0usize;
+            let mut proof_pos = 0usize;
+
+            for &use_stream_for_both in proof_flags {
+                let a = match Self::next_multiproof_operand(leaves, &hashes, &mut leaf_pos, &mut hash_pos)
+                {
+                    Some(v) => v,
+                    None => return false,
+                };
+                let b = if use_stream_for_both {
+                    match Self::next_multiproof_operand(
+                        leaves,
+                        &hashes,
+                        &mut leaf_pos,
+                        &mut hash_pos,
+                    ) {
+                        Some(v) => v,
+                        None => return false,
+                    }
+                } else {
+                    if proof_pos >= proof.len() {
+                        return false;
+                    }
+                    let v = proof[proof_pos];
+                    proof_pos += 1;
+                    v
+                };
+
+                hashes.push(Self::hash_pair_sorted(&a, &b, hash_algo));
+            }
+
+            // Degenerate case: no folding ever happens, so leaf_pos/proof_pos
+            // never advance past 0. The length check above already guarantees
+            // exactly one of leaves/proof holds the single operand, so just
+            // compare it to root directly rather than applying the
+            // consumption gate below.
+            if total_hashes == 0 {
+                let only_operand = leaves.first().copied().or_else(|| proof.first().copied());
+                return only_operand == Some(*root);
+            }
+
+            let computed_root = if let Some(&last) = hashes.last() {
+                last
+            } else if let Some(&only_leaf) = leaves.first() {
+                only_leaf
+            } else {
+                proof[0]
+            };
+
+            leaf_pos == leaves.len() && proof_pos == proof.len() && computed_root == *root
+        }
+
+        /// Pop the next operand from the leaf stream, falling back to the
+        /// already-computed-hash stream once the leaves are exhausted.
+        fn next_multiproof_operand(
+            leaves: &[[u8; 32]],
+            computed: &[[u8; 32]],
+            leaf_pos: &mut usize,
+            hash_pos: &mut usize,
+        ) -> Option<[u8; 32]> {
+            if *leaf_pos < leaves.len() {
+                let v = leaves[*leaf_pos];
+                *leaf_pos += 1;
+                Some(v)
+            } else if *hash_pos < computed.len() {
+                let v = computed[*hash_pos];
+                *hash_pos += 1;
+                Some(v)
+            } else {
+                None
+            }
+        }
+
+        /// Hash two nodes in sorted byte order: `H(min(a,b) || max(a,b))`.
+        fn hash_pair_sorted(a: &[u8; 32], b: &[u8; 32], hash_algo: HashAlgo) -> [u8; 32] {
+            let mut input = [0u8; 64];
+            if a <= b {
+                input[..32].copy_from_slice(a);
+                input[32..].copy_from_slice(b);
+            } else {
+                input[..32].copy_from_slice(b);
+                input[32..].copy_from_slice(a);
+            }
+
+            Self::hash_bytes64(&input, hash_algo)
+        }
+
         /// Compute session ID from caller, scope, and block number.
         fn compute_session_id(&self, caller: &Address, scope_id: &[u8; 32]) -> [u8; 32] {
             use ink::env::hash::{Blake2x256, HashOutput};
@@ -481,6 +1582,45 @@ mod access_registry {
             ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
             output
         }
+
+        /// Compute a delegated child session's ID from the parent session ID,
+        /// a per-parent delegation nonce, caller, scope, and block number.
+        /// Mixing in `parent_session_id` and `nonce` keeps it distinct from
+        /// [`Self::compute_session_id`] (used by `create_session`/
+        /// `request_session`), from a second delegation by the same caller
+        /// for the same `child_scope_id` in the same block from a
+        /// *different* parent, and from a second delegation from the *same*
+        /// parent (e.g. after the first child was revoked) — all of which
+        /// would otherwise collide and silently overwrite an existing
+        /// `SessionGrant`.
+        fn compute_child_session_id(
+            &self,
+            parent_session_id: &[u8; 32],
+            caller: &Address,
+            scope_id: &[u8; 32],
+            nonce: u64,
+        ) -> [u8; 32] {
+            use ink::env::hash::{Blake2x256, HashOutput};
+
+            let mut input = ink::prelude::vec::Vec::new();
+            input.extend_from_slice(parent_session_id);
+            input.extend_from_slice(caller.as_ref());
+            input.extend_from_slice(scope_id);
+            input.extend_from_slice(&self.env().block_number().to_le_bytes());
+            input.extend_from_slice(&nonce.to_le_bytes());
+
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
+            output
+        }
+
+        /// Current `ScopeRequirement::version` for `scope_id`, or 0 if the
+        /// scope has never had requirements set.
+        fn current_scope_version(&self, scope_id: &[u8; 32]) -> u32 {
+            self.scope_requirements
+                .get(scope_id)
+                .map_or(0, |requirement| requirement.version)
+        }
     }
 
     #[cfg(test)]
@@ -575,19 +1715,348 @@ mod access_registry {
                 .create_session(session_id, eph_pub_key, scope_id, expires_at_block)
                 .unwrap();
 
-            assert!(contract.revoke_session(session_id).is_ok());
+            assert!(contract.revoke_session(session_id).is_ok());
+
+            let grant = contract.get_session(session_id).unwrap();
+            assert!(grant.is_revoked);
+        }
+
+        #[ink::test]
+        fn revoke_session_fails_for_unknown() {
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x99u8; 32];
+            assert_eq!(
+                contract.revoke_session(session_id),
+                Err(Error::SessionNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn prove_session_fails_for_unknown_session() {
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x99u8; 32];
+            assert_eq!(
+                contract.prove_session(session_id, [0u8; 32], [0u8; 65]),
+                Err(Error::SessionNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn prove_session_fails_for_revoked_session() {
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x01u8; 32];
+            let eph_pub_key = ink::prelude::vec![0x02u8; 33];
+            let scope_id = [0x03u8; 32];
+
+            contract
+                .create_session(session_id, eph_pub_key, scope_id, 1000)
+                .unwrap();
+            contract.revoke_session(session_id).unwrap();
+
+            assert_eq!(
+                contract.prove_session(session_id, [0u8; 32], [0u8; 65]),
+                Err(Error::SessionExpired)
+            );
+        }
+
+        #[ink::test]
+        fn prove_session_fails_for_expired_session() {
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x01u8; 32];
+            let eph_pub_key = ink::prelude::vec![0x02u8; 33];
+            let scope_id = [0x03u8; 32];
+
+            // expires_at_block of 0 is already in the past once the chain has
+            // advanced past genesis.
+            contract
+                .create_session(session_id, eph_pub_key, scope_id, 0)
+                .unwrap();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(
+                contract.prove_session(session_id, [0u8; 32], [0u8; 65]),
+                Err(Error::SessionExpired)
+            );
+        }
+
+        #[ink::test]
+        fn prove_session_fails_for_wrong_challenge() {
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x01u8; 32];
+            let eph_pub_key = ink::prelude::vec![0x02u8; 33];
+            let scope_id = [0x03u8; 32];
+
+            contract
+                .create_session(session_id, eph_pub_key, scope_id, 1000)
+                .unwrap();
+
+            // An arbitrary challenge that does not match
+            // H(session_id || caller || block_number) is rejected before any
+            // signature recovery is attempted.
+            assert_eq!(
+                contract.prove_session(session_id, [0x42u8; 32], [0u8; 65]),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn delegate_session_fails_for_unknown_parent() {
+            let mut contract = AccessRegistry::new();
+            let result = contract.delegate_session(
+                [0x99u8; 32],
+                ink::prelude::vec![0x02u8; 33],
+                [0x01u8; 32],
+                100,
+                [0u8; 65],
+            );
+            assert_eq!(result, Err(Error::SessionNotFound));
+        }
+
+        #[ink::test]
+        fn delegate_session_fails_for_revoked_parent() {
+            let mut contract = AccessRegistry::new();
+            let parent_id = [0x01u8; 32];
+            let scope_id = [0x03u8; 32];
+
+            contract
+                .create_session(parent_id, ink::prelude::vec![0x02u8; 33], scope_id, 1000)
+                .unwrap();
+            contract.revoke_session(parent_id).unwrap();
+
+            let result = contract.delegate_session(
+                parent_id,
+                ink::prelude::vec![0x04u8; 33],
+                scope_id,
+                100,
+                [0u8; 65],
+            );
+            assert_eq!(result, Err(Error::SessionExpired));
+        }
+
+        #[ink::test]
+        fn delegate_session_fails_with_invalid_signature() {
+            let mut contract = AccessRegistry::new();
+            let parent_id = [0x01u8; 32];
+            let scope_id = [0x03u8; 32];
+
+            contract
+                .create_session(parent_id, ink::prelude::vec![0x02u8; 33], scope_id, 1000)
+                .unwrap();
+
+            // A zeroed signature cannot recover to the parent's ephemeral key.
+            let result = contract.delegate_session(
+                parent_id,
+                ink::prelude::vec![0x04u8; 33],
+                scope_id,
+                100,
+                [0u8; 65],
+            );
+            assert_eq!(result, Err(Error::InvalidSignature));
+        }
+
+        #[ink::test]
+        fn delegate_session_fails_for_stale_parent_scope_version() {
+            let mut contract = AccessRegistry::new();
+            let parent_id = [0x01u8; 32];
+            let scope_id = [0x03u8; 32];
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+            contract
+                .create_session(parent_id, ink::prelude::vec![0x02u8; 33], scope_id, 1000)
+                .unwrap();
+
+            // Rotating the scope's requirements bumps its version past the
+            // parent's recorded `scope_version`, so it can no longer mint a
+            // fresh, fully-valid child session.
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![[0xABu8; 32]], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+
+            let result = contract.delegate_session(
+                parent_id,
+                ink::prelude::vec![0x04u8; 33],
+                scope_id,
+                100,
+                [0u8; 65],
+            );
+            assert_eq!(result, Err(Error::StaleScopeVersion));
+        }
+
+        #[ink::test]
+        fn compute_child_session_id_differs_by_parent_and_nonce() {
+            // Same caller/scope/block: a different parent, or a second
+            // delegation nonce from the same parent, must not collide.
+            let contract = AccessRegistry::new();
+            let caller = Address::from([0x02; 20]);
+            let scope_id = [0x03u8; 32];
+            let parent_a = [0x01u8; 32];
+            let parent_b = [0x02u8; 32];
+
+            let id_a0 = contract.compute_child_session_id(&parent_a, &caller, &scope_id, 0);
+            let id_b0 = contract.compute_child_session_id(&parent_b, &caller, &scope_id, 0);
+            let id_a1 = contract.compute_child_session_id(&parent_a, &caller, &scope_id, 1);
+
+            assert_ne!(id_a0, id_b0);
+            assert_ne!(id_a0, id_a1);
+        }
+
+        #[ink::test]
+        fn revoke_session_works_with_no_children() {
+            // A session with no delegated children revokes normally; the
+            // cascade walk over an empty child list is a no-op.
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x01u8; 32];
+            contract
+                .create_session(session_id, ink::prelude::vec![0x02u8; 33], [0x03u8; 32], 1000)
+                .unwrap();
+
+            assert!(contract.revoke_session(session_id).is_ok());
+            assert!(contract.get_session(session_id).unwrap().is_revoked);
+        }
+
+        #[ink::test]
+        fn authorize_generator_works() {
+            let mut contract = AccessRegistry::new();
+            let generator = Address::from([0x05; 20]);
+            assert!(!contract.is_generator(generator));
+            assert!(contract.authorize_generator(generator).is_ok());
+            assert!(contract.is_generator(generator));
+        }
+
+        #[ink::test]
+        fn authorized_integrator_can_grant_and_revoke_entitlements() {
+            let mut contract = AccessRegistry::new();
+            let integrator = Address::from([0x06; 20]);
+            let account = Address::from([0x07; 20]);
+
+            assert!(!contract.is_integrator(integrator));
+            assert!(contract.authorize_integrator(integrator).is_ok());
+            assert!(contract.is_integrator(integrator));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(integrator);
+
+            assert!(
+                contract
+                    .grant_entitlement(account, EntitlementLevel::Vip)
+                    .is_ok()
+            );
+            assert_eq!(contract.get_entitlement(account), EntitlementLevel::Vip);
+            assert!(contract.revoke_entitlement(account).is_ok());
+            assert_eq!(contract.get_entitlement(account), EntitlementLevel::None);
+        }
+
+        #[ink::test]
+        fn unauthorized_caller_cannot_grant_entitlements() {
+            let mut contract = AccessRegistry::new();
+            let stranger = Address::from([0x08; 20]);
+            let account = Address::from([0x07; 20]);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(stranger);
+
+            assert_eq!(
+                contract.grant_entitlement(account, EntitlementLevel::Vip),
+                Err(Error::NotAuthorizedIntegrator)
+            );
+        }
+
+        #[ink::test]
+        fn request_key_generation_works() {
+            let mut contract = AccessRegistry::new();
+            let scope_id = [0x01u8; 32];
+
+            let request_id = contract.request_key_generation(scope_id, 100).unwrap();
+            let request = contract.get_pending_request(request_id).unwrap();
+
+            assert_eq!(request.scope_id, scope_id);
+            assert_eq!(request.duration_blocks, 100);
+            assert_eq!(request.status, RequestStatus::Pending);
+        }
+
+        #[ink::test]
+        fn fulfill_session_request_fails_for_non_generator() {
+            let mut contract = AccessRegistry::new();
+            let request_id = contract.request_key_generation([0x01u8; 32], 100).unwrap();
+
+            let result = contract.fulfill_session_request(
+                request_id,
+                ink::prelude::vec![0x02u8; 33],
+                ink::prelude::vec![0x03u8; 16],
+            );
+            assert_eq!(result, Err(Error::NotAuthorizedGenerator));
+        }
+
+        #[ink::test]
+        fn fulfill_session_request_works() {
+            let mut contract = AccessRegistry::new();
+            let generator = Address::from([0x05; 20]);
+            contract.authorize_generator(generator).unwrap();
+
+            let scope_id = [0x01u8; 32];
+            let request_id = contract.request_key_generation(scope_id, 100).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(generator);
+
+            let root_before = contract.sessions_root();
+
+            let eph_pub_key = ink::prelude::vec![0x02u8; 33];
+            let session_id = contract
+                .fulfill_session_request(request_id, eph_pub_key.clone(), ink::prelude::vec![0xAA; 16])
+                .unwrap();
+
+            let grant = contract.get_session(session_id).unwrap();
+            assert_eq!(grant.eph_pub_key, eph_pub_key);
+            assert_eq!(grant.scope_id, scope_id);
+
+            // Fulfilling a key-generation request is a session-issuing path
+            // too, so it must be folded into the aggregate commitment.
+            assert_ne!(root_before, contract.sessions_root());
+
+            let request = contract.get_pending_request(request_id).unwrap();
+            assert_eq!(request.status, RequestStatus::Fulfilled);
+            assert_eq!(request.session_id, Some(session_id));
+            assert_eq!(request.encrypted_key_share, Some(ink::prelude::vec![0xAA; 16]));
+        }
+
+        #[ink::test]
+        fn fulfill_session_request_fails_when_already_fulfilled() {
+            let mut contract = AccessRegistry::new();
+            let generator = Address::from([0x05; 20]);
+            contract.authorize_generator(generator).unwrap();
+
+            let request_id = contract.request_key_generation([0x01u8; 32], 100).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(generator);
+            contract
+                .fulfill_session_request(request_id, ink::prelude::vec![0x02u8; 33], ink::prelude::vec![])
+                .unwrap();
+
+            let result = contract.fulfill_session_request(
+                request_id,
+                ink::prelude::vec![0x02u8; 33],
+                ink::prelude::vec![],
+            );
+            assert_eq!(result, Err(Error::RequestNotPending));
+        }
+
+        #[ink::test]
+        fn cancel_timed_out_request_fails_before_timeout() {
+            let mut contract = AccessRegistry::new();
+            let request_id = contract.request_key_generation([0x01u8; 32], 100).unwrap();
 
-            let grant = contract.get_session(session_id).unwrap();
-            assert!(grant.is_revoked);
+            assert_eq!(
+                contract.cancel_timed_out_request(request_id),
+                Err(Error::RequestNotTimedOut)
+            );
         }
 
         #[ink::test]
-        fn revoke_session_fails_for_unknown() {
+        fn cancel_timed_out_request_fails_for_unknown() {
             let mut contract = AccessRegistry::new();
-            let session_id = [0x99u8; 32];
             assert_eq!(
-                contract.revoke_session(session_id),
-                Err(Error::SessionNotFound)
+                contract.cancel_timed_out_request([0x99u8; 32]),
+                Err(Error::RequestNotFound)
             );
         }
 
@@ -607,7 +2076,7 @@ mod access_registry {
 
             assert!(
                 contract
-                    .set_scope_requirement(scope_id, required.clone(), true)
+                    .set_scope_requirement(scope_id, required.clone(), true, HashAlgo::Blake2x256, false)
                     .is_ok()
             );
 
@@ -616,6 +2085,74 @@ mod access_registry {
             assert!(req.active);
         }
 
+        #[ink::test]
+        fn set_scope_requirement_bumps_version() {
+            let mut contract = AccessRegistry::new();
+            let scope_id = [0x01u8; 32];
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+            assert_eq!(contract.get_scope_requirement(scope_id).unwrap().version, 1);
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+            assert_eq!(contract.get_scope_requirement(scope_id).unwrap().version, 2);
+        }
+
+        #[ink::test]
+        fn is_session_valid_becomes_false_after_scope_rotation() {
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x01u8; 32];
+            let scope_id = [0x03u8; 32];
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+            contract
+                .create_session(session_id, ink::prelude::vec![0x02u8; 33], scope_id, 1000)
+                .unwrap();
+
+            assert!(contract.is_session_valid(session_id));
+
+            // Rotating the scope's requirements bumps its version past the
+            // session's recorded `scope_version`.
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![[0xABu8; 32]], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+
+            assert!(!contract.is_session_valid(session_id));
+        }
+
+        #[ink::test]
+        fn is_session_valid_false_for_unknown_session() {
+            let contract = AccessRegistry::new();
+            assert!(!contract.is_session_valid([0x99u8; 32]));
+        }
+
+        #[ink::test]
+        fn prove_session_fails_for_stale_scope_version() {
+            let mut contract = AccessRegistry::new();
+            let session_id = [0x01u8; 32];
+            let scope_id = [0x03u8; 32];
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+            contract
+                .create_session(session_id, ink::prelude::vec![0x02u8; 33], scope_id, 1000)
+                .unwrap();
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![[0xABu8; 32]], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+
+            assert_eq!(
+                contract.prove_session(session_id, [0u8; 32], [0u8; 65]),
+                Err(Error::StaleScopeVersion)
+            );
+        }
+
         #[ink::test]
         fn verify_merkle_proof_works() {
             // Build a simple 2-leaf Merkle tree
@@ -641,7 +2178,9 @@ mod access_registry {
                 &leaf_a,
                 &proof_path,
                 &proof_indices,
-                &root
+                &root,
+                HashAlgo::Blake2x256,
+                false
             ));
 
             // Invalid proof should fail
@@ -650,10 +2189,320 @@ mod access_registry {
                 &wrong_leaf,
                 &proof_path,
                 &proof_indices,
-                &root
+                &root,
+                HashAlgo::Blake2x256,
+                false
+            ));
+        }
+
+        #[ink::test]
+        fn verify_merkle_proof_keccak256_works() {
+            // Same 2-leaf tree as `verify_merkle_proof_works`, but folded with
+            // keccak256 to exercise the non-default `HashAlgo`.
+            use ink::env::hash::{Keccak256, HashOutput};
+
+            let leaf_a = [0x01u8; 32];
+            let leaf_b = [0x02u8; 32];
+
+            let mut root_input = [0u8; 64];
+            root_input[..32].copy_from_slice(&leaf_a);
+            root_input[32..].copy_from_slice(&leaf_b);
+            let mut root = <Keccak256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Keccak256>(&root_input, &mut root);
+
+            let proof_path = ink::prelude::vec![leaf_b];
+            let proof_indices = ink::prelude::vec![0u8];
+
+            assert!(AccessRegistry::verify_merkle_proof(
+                &leaf_a,
+                &proof_path,
+                &proof_indices,
+                &root,
+                HashAlgo::Keccak256,
+                false
+            ));
+
+            // The same proof verified against Blake2x256 must fail: the
+            // scope's configured algorithm has to match the tree it was
+            // built with.
+            assert!(!AccessRegistry::verify_merkle_proof(
+                &leaf_a,
+                &proof_path,
+                &proof_indices,
+                &root,
+                HashAlgo::Blake2x256,
+                false
+            ));
+        }
+
+        #[ink::test]
+        fn verify_merkle_proof_commutative_works() {
+            // Same 2-leaf tree, but verified in commutative mode: the pair is
+            // sorted before hashing and `proof_indices` is left empty.
+            let leaf_a = [0x01u8; 32];
+            let leaf_b = [0x02u8; 32];
+            let root = AccessRegistry::hash_pair_sorted(&leaf_a, &leaf_b, HashAlgo::Blake2x256);
+
+            let proof_path = ink::prelude::vec![leaf_b];
+            let empty_indices = ink::prelude::vec::Vec::new();
+
+            assert!(AccessRegistry::verify_merkle_proof(
+                &leaf_a,
+                &proof_path,
+                &empty_indices,
+                &root,
+                HashAlgo::Blake2x256,
+                true
+            ));
+
+            // A non-matching leaf must still fail.
+            let wrong_leaf = [0x99u8; 32];
+            assert!(!AccessRegistry::verify_merkle_proof(
+                &wrong_leaf,
+                &proof_path,
+                &empty_indices,
+                &root,
+                HashAlgo::Blake2x256,
+                true
+            ));
+        }
+
+        #[ink::test]
+        fn record_merkle_proof_roundtrips_with_verify_merkle_proof() {
+            let contract = AccessRegistry::new();
+            // Odd leaf count so the recorder has to exercise the
+            // duplicate-last-node rule at the top level.
+            let leaves = ink::prelude::vec![
+                [0x01u8; 32],
+                [0x02u8; 32],
+                [0x03u8; 32],
+            ];
+
+            for (leaf_index, leaf) in leaves.iter().enumerate() {
+                let (proof_path, proof_indices, root) = contract
+                    .record_merkle_proof(leaves.clone(), leaf_index as u32, HashAlgo::Blake2x256, false)
+                    .unwrap();
+
+                assert!(AccessRegistry::verify_merkle_proof(
+                    leaf,
+                    &proof_path,
+                    &proof_indices,
+                    &root,
+                    HashAlgo::Blake2x256,
+                    false
+                ));
+            }
+        }
+
+        #[ink::test]
+        fn record_merkle_proof_roundtrips_in_commutative_mode() {
+            let contract = AccessRegistry::new();
+            let leaves = ink::prelude::vec![[0x0Au8; 32], [0x0Bu8; 32], [0x0Cu8; 32], [0x0Du8; 32]];
+
+            let (proof_path, _proof_indices, root) = contract
+                .record_merkle_proof(leaves.clone(), 2, HashAlgo::Keccak256, true)
+                .unwrap();
+
+            assert!(AccessRegistry::verify_merkle_proof(
+                &leaves[2],
+                &proof_path,
+                &ink::prelude::vec::Vec::new(),
+                &root,
+                HashAlgo::Keccak256,
+                true
+            ));
+        }
+
+        #[ink::test]
+        fn record_merkle_proof_rejects_out_of_range_index() {
+            let contract = AccessRegistry::new();
+            let leaves = ink::prelude::vec![[0x01u8; 32]];
+            assert!(contract
+                .record_merkle_proof(leaves, 5, HashAlgo::Blake2x256, false)
+                .is_none());
+        }
+
+        #[ink::test]
+        fn verify_merkle_multiproof_works() {
+            // 4-leaf tree: root = H(H(A,B), H(C,D)), proving leaves A and C
+            // with proof = [B, D] and flags = [false, false, true].
+            use ink::env::hash::{Blake2x256, HashOutput};
+
+            fn hash_sorted(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+                let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+                let mut input = [0u8; 64];
+                input[..32].copy_from_slice(&lo);
+                input[32..].copy_from_slice(&hi);
+                let mut output = <Blake2x256 as HashOutput>::Type::default();
+                ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
+                output
+            }
+
+            let leaf_a = [0x01u8; 32];
+            let leaf_b = [0x02u8; 32];
+            let leaf_c = [0x03u8; 32];
+            let leaf_d = [0x04u8; 32];
+
+            let node_ab = hash_sorted(leaf_a, leaf_b);
+            let node_cd = hash_sorted(leaf_c, leaf_d);
+            let root = hash_sorted(node_ab, node_cd);
+
+            let leaves = ink::prelude::vec![leaf_a, leaf_c];
+            let proof = ink::prelude::vec![leaf_b, leaf_d];
+            let proof_flags = ink::prelude::vec![false, false, true];
+
+            assert!(AccessRegistry::verify_merkle_multiproof(
+                &leaves,
+                &proof,
+                &proof_flags,
+                &root,
+                HashAlgo::Blake2x256
+            ));
+
+            let mut wrong_root = root;
+            wrong_root[0] ^= 0xFF;
+            assert!(!AccessRegistry::verify_merkle_multiproof(
+                &leaves,
+                &proof,
+                &proof_flags,
+                &wrong_root,
+                HashAlgo::Blake2x256
+            ));
+        }
+
+        #[ink::test]
+        fn verify_merkle_multiproof_rejects_unconsumed_leftovers() {
+            // Same 4-leaf tree as `verify_merkle_multiproof_works`, but with
+            // an extra unrelated leaf appended to `leaves` that the flags
+            // never fold in. The algorithm must reject rather than ignore
+            // the leftover operand.
+            use ink::env::hash::{Blake2x256, HashOutput};
+
+            fn hash_sorted(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+                let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+                let mut input = [0u8; 64];
+                input[..32].copy_from_slice(&lo);
+                input[32..].copy_from_slice(&hi);
+                let mut output = <Blake2x256 as HashOutput>::Type::default();
+                ink::env::hash_bytes::<Blake2x256>(&input, &mut output);
+                output
+            }
+
+            let leaf_a = [0x01u8; 32];
+            let leaf_b = [0x02u8; 32];
+            let leaf_c = [0x03u8; 32];
+            let leaf_d = [0x04u8; 32];
+            let leftover = [0x05u8; 32];
+
+            let node_ab = hash_sorted(leaf_a, leaf_b);
+            let node_cd = hash_sorted(leaf_c, leaf_d);
+            let root = hash_sorted(node_ab, node_cd);
+
+            let leaves = ink::prelude::vec![leaf_a, leaf_c, leftover];
+            let proof = ink::prelude::vec![leaf_b, leaf_d];
+            let proof_flags = ink::prelude::vec![false, false, true];
+
+            assert!(!AccessRegistry::verify_merkle_multiproof(
+                &leaves,
+                &proof,
+                &proof_flags,
+                &root,
+                HashAlgo::Blake2x256
             ));
         }
 
+        #[ink::test]
+        fn verify_merkle_multiproof_accepts_single_leaf_as_root() {
+            // Degenerate one-operand tree: a single leaf with no proof and
+            // no flags, where the leaf itself is the root. The fold loop
+            // never runs, so the consumption check must not reject this.
+            let leaf = [0x01u8; 32];
+
+            assert!(AccessRegistry::verify_merkle_multiproof(
+                &[leaf],
+                &[],
+                &[],
+                &leaf,
+                HashAlgo::Blake2x256
+            ));
+
+            let mut wrong_root = leaf;
+            wrong_root[0] ^= 0xFF;
+            assert!(!AccessRegistry::verify_merkle_multiproof(
+                &[leaf],
+                &[],
+                &[],
+                &wrong_root,
+                HashAlgo::Blake2x256
+            ));
+        }
+
+        #[ink::test]
+        fn request_session_multi_works_with_valid_multiproof() {
+            use ink::env::hash::{Blake2x256, HashOutput};
+
+            let mut contract = AccessRegistry::new();
+            let scope_id = [0x01u8; 32];
+            let caller = Address::default();
+
+            let leaf_a = [0xABu8; 32];
+            let leaf_b = [0xCDu8; 32];
+
+            let (lo, hi) = if leaf_a <= leaf_b {
+                (leaf_a, leaf_b)
+            } else {
+                (leaf_b, leaf_a)
+            };
+            let mut root_input = [0u8; 64];
+            root_input[..32].copy_from_slice(&lo);
+            root_input[32..].copy_from_slice(&hi);
+            let mut root = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&root_input, &mut root);
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![leaf_a], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+
+            let result = contract.request_session_multi_with_root(
+                caller,
+                ink::prelude::vec![0x02u8; 33],
+                scope_id,
+                100,
+                ink::prelude::vec![leaf_a],
+                ink::prelude::vec![leaf_b],
+                ink::prelude::vec![false],
+                root,
+            );
+
+            assert!(result.is_ok());
+            let session_id = result.unwrap();
+            assert_eq!(contract.get_session(session_id).unwrap().scope_id, scope_id);
+        }
+
+        #[ink::test]
+        fn request_session_multi_fails_with_missing_attribute() {
+            let mut contract = AccessRegistry::new();
+            let scope_id = [0x01u8; 32];
+            let caller = Address::default();
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![[0xABu8; 32]], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+
+            let result = contract.request_session_multi_with_root(
+                caller,
+                ink::prelude::vec![0x02u8; 33],
+                scope_id,
+                100,
+                ink::prelude::vec![],
+                ink::prelude::vec![],
+                ink::prelude::vec![],
+                [0u8; 32],
+            );
+
+            assert_eq!(result, Err(Error::MissingRequiredAttribute));
+        }
+
         #[ink::test]
         fn request_session_fails_without_attribute_store() {
             let mut contract = AccessRegistry::new();
@@ -661,7 +2510,7 @@ mod access_registry {
 
             // Set up scope requirement
             contract
-                .set_scope_requirement(scope_id, ink::prelude::vec![], true)
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
                 .unwrap();
 
             let result = contract.request_session(
@@ -669,21 +2518,27 @@ mod access_registry {
                 scope_id,
                 100,
                 ink::prelude::vec![],
-                [0u8; 32],
             );
 
             assert_eq!(result, Err(Error::AttributeStoreNotConfigured));
         }
 
+        // The remaining `request_session` scenarios exercise
+        // `request_session_with_root` directly: once `attribute_store` is
+        // configured, `request_session` resolves the root via a
+        // cross-contract call, which `#[ink::test]`'s off-chain environment
+        // cannot service without a deployed `attribute_store` instance. The
+        // root-resolution step itself is covered by
+        // `request_session_fails_without_attribute_store` above.
+
         #[ink::test]
         fn request_session_fails_for_unknown_scope() {
             let mut contract = AccessRegistry::new();
-            let attribute_store = Address::from([0x99; 20]);
             let scope_id = [0x01u8; 32];
+            let caller = Address::default();
 
-            contract.set_attribute_store(attribute_store).unwrap();
-
-            let result = contract.request_session(
+            let result = contract.request_session_with_root(
+                caller,
                 ink::prelude::vec![0x02u8; 33],
                 scope_id,
                 100,
@@ -697,15 +2552,15 @@ mod access_registry {
         #[ink::test]
         fn request_session_fails_for_inactive_scope() {
             let mut contract = AccessRegistry::new();
-            let attribute_store = Address::from([0x99; 20]);
             let scope_id = [0x01u8; 32];
+            let caller = Address::default();
 
-            contract.set_attribute_store(attribute_store).unwrap();
             contract
-                .set_scope_requirement(scope_id, ink::prelude::vec![], false)
+                .set_scope_requirement(scope_id, ink::prelude::vec![], false, HashAlgo::Blake2x256, false)
                 .unwrap();
 
-            let result = contract.request_session(
+            let result = contract.request_session_with_root(
+                caller,
                 ink::prelude::vec![0x02u8; 33],
                 scope_id,
                 100,
@@ -719,15 +2574,15 @@ mod access_registry {
         #[ink::test]
         fn request_session_works_with_no_requirements() {
             let mut contract = AccessRegistry::new();
-            let attribute_store = Address::from([0x99; 20]);
             let scope_id = [0x01u8; 32];
+            let caller = Address::default();
 
-            contract.set_attribute_store(attribute_store).unwrap();
             contract
-                .set_scope_requirement(scope_id, ink::prelude::vec![], true)
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
                 .unwrap();
 
-            let result = contract.request_session(
+            let result = contract.request_session_with_root(
+                caller,
                 ink::prelude::vec![0x02u8; 33],
                 scope_id,
                 100,
@@ -745,9 +2600,7 @@ mod access_registry {
         fn request_session_works_with_valid_proofs() {
             let mut contract = AccessRegistry::new();
             let scope_id = [0x01u8; 32];
-            let attribute_store = Address::from([0x99; 20]);
-
-            contract.set_attribute_store(attribute_store).unwrap();
+            let caller = Address::default();
 
             // Build Merkle tree with one required attribute
             use ink::env::hash::{Blake2x256, HashOutput};
@@ -764,7 +2617,7 @@ mod access_registry {
 
             // Set scope requirement
             contract
-                .set_scope_requirement(scope_id, ink::prelude::vec![attr_hash], true)
+                .set_scope_requirement(scope_id, ink::prelude::vec![attr_hash], true, HashAlgo::Blake2x256, false)
                 .unwrap();
 
             // Create proof
@@ -774,7 +2627,8 @@ mod access_registry {
                 proof_indices: ink::prelude::vec![0],
             };
 
-            let result = contract.request_session(
+            let result = contract.request_session_with_root(
+                caller,
                 ink::prelude::vec![0x02u8; 33],
                 scope_id,
                 100,
@@ -792,15 +2646,13 @@ mod access_registry {
         fn request_session_fails_with_invalid_proof() {
             let mut contract = AccessRegistry::new();
             let scope_id = [0x01u8; 32];
-            let attribute_store = Address::from([0x99; 20]);
-
-            contract.set_attribute_store(attribute_store).unwrap();
+            let caller = Address::default();
 
             let attr_hash = [0xABu8; 32];
 
             // Set scope requirement
             contract
-                .set_scope_requirement(scope_id, ink::prelude::vec![attr_hash], true)
+                .set_scope_requirement(scope_id, ink::prelude::vec![attr_hash], true, HashAlgo::Blake2x256, false)
                 .unwrap();
 
             // Create proof with wrong root
@@ -810,7 +2662,8 @@ mod access_registry {
                 proof_indices: ink::prelude::vec![0],
             };
 
-            let result = contract.request_session(
+            let result = contract.request_session_with_root(
+                caller,
                 ink::prelude::vec![0x02u8; 33],
                 scope_id,
                 100,
@@ -825,16 +2678,15 @@ mod access_registry {
         fn request_session_fails_with_missing_attribute() {
             let mut contract = AccessRegistry::new();
             let scope_id = [0x01u8; 32];
-            let attribute_store = Address::from([0x99; 20]);
-
-            contract.set_attribute_store(attribute_store).unwrap();
+            let caller = Address::default();
 
             // Require an attribute but don't provide proof for it
             contract
-                .set_scope_requirement(scope_id, ink::prelude::vec![[0xABu8; 32]], true)
+                .set_scope_requirement(scope_id, ink::prelude::vec![[0xABu8; 32]], true, HashAlgo::Blake2x256, false)
                 .unwrap();
 
-            let result = contract.request_session(
+            let result = contract.request_session_with_root(
+                caller,
                 ink::prelude::vec![0x02u8; 33],
                 scope_id,
                 100,
@@ -844,5 +2696,105 @@ mod access_registry {
 
             assert_eq!(result, Err(Error::MissingRequiredAttribute));
         }
+
+        #[ink::test]
+        fn sessions_root_is_zero_with_no_sessions() {
+            let contract = AccessRegistry::new();
+            assert_eq!(contract.sessions_root(), [0u8; 32]);
+        }
+
+        #[ink::test]
+        fn sessions_root_updates_as_sessions_are_issued() {
+            let mut contract = AccessRegistry::new();
+            let scope_id = [0x01u8; 32];
+            let caller = Address::default();
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+
+            let root_before = contract.sessions_root();
+
+            contract
+                .request_session_with_root(
+                    caller,
+                    ink::prelude::vec![0x02u8; 33],
+                    scope_id,
+                    100,
+                    ink::prelude::vec![],
+                    [0u8; 32],
+                )
+                .unwrap();
+
+            let root_after = contract.sessions_root();
+            assert_ne!(root_before, root_after);
+        }
+
+        #[ink::test]
+        fn verify_session_inclusion_works() {
+            let mut contract = AccessRegistry::new();
+            let scope_id = [0x01u8; 32];
+            let caller = Address::default();
+
+            contract
+                .set_scope_requirement(scope_id, ink::prelude::vec![], true, HashAlgo::Blake2x256, false)
+                .unwrap();
+
+            // Two sessions so the aggregate tree has a real sibling pair.
+            let session_id_a = contract
+                .request_session_with_root(
+                    caller,
+                    ink::prelude::vec![0x02u8; 33],
+                    scope_id,
+                    100,
+                    ink::prelude::vec![],
+                    [0u8; 32],
+                )
+                .unwrap();
+
+            let other_caller = Address::from([0x07u8; 20]);
+            contract
+                .request_session_with_root(
+                    other_caller,
+                    ink::prelude::vec![0x03u8; 33],
+                    scope_id,
+                    100,
+                    ink::prelude::vec![],
+                    [0u8; 32],
+                )
+                .unwrap();
+
+            let root = contract.sessions_root();
+            let grant_a = contract.get_session(session_id_a).unwrap();
+
+            let leaf_b = AccessRegistry::session_leaf_hash(
+                &contract
+                    .compute_session_id(&other_caller, &scope_id),
+                &scope_id,
+                grant_a.expires_at_block,
+                &other_caller,
+            );
+
+            assert!(contract.verify_session_inclusion(
+                session_id_a,
+                scope_id,
+                grant_a.expires_at_block,
+                caller,
+                ink::prelude::vec![leaf_b],
+                ink::prelude::vec![0u8],
+                root,
+            ));
+
+            // A wrong subject must not verify against the same proof.
+            assert!(!contract.verify_session_inclusion(
+                session_id_a,
+                scope_id,
+                grant_a.expires_at_block,
+                other_caller,
+                ink::prelude::vec![leaf_b],
+                ink::prelude::vec![0u8],
+                root,
+            ));
+        }
     }
 }
@@ -3,11 +3,53 @@
 #[ink::contract]
 mod payment_integration {
     use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
     use ink::storage::Mapping;
 
     /// Maximum length for string inputs (`payment_provider`, `transaction_id`)
     const MAX_STRING_LENGTH: usize = 256;
 
+    /// Default cap on [`PaymentIntegration::retry_payment`] attempts for a
+    /// `Failed` payment, similar to the bounded retry loop used in Lightning
+    /// invoice payment handling.
+    const DEFAULT_MAX_RETRIES: u8 = 3;
+
+    /// `ref_time` weight limit applied to each cross-contract call to
+    /// `access_registry`, so a misbehaving registry cannot exhaust the
+    /// caller's gas.
+    const CROSS_CALL_REF_TIME_LIMIT: u64 = 5_000_000_000;
+    /// `proof_size` weight limit applied to each cross-contract call.
+    const CROSS_CALL_PROOF_SIZE_LIMIT: u64 = 1_000_000;
+    /// Storage deposit limit applied to each cross-contract call.
+    /// `access_registry` only mutates its own `entitlements` mapping, which
+    /// this contract does not pay the deposit for.
+    const CROSS_CALL_STORAGE_DEPOSIT_LIMIT: Balance = 0;
+
+    /// Cross-contract interface implemented by the configured
+    /// `access_registry`.
+    #[ink::trait_definition]
+    pub trait AccessRegistry {
+        /// Grant `account` the given entitlement level.
+        #[ink(message)]
+        fn grant_entitlement(&mut self, account: Address, level: EntitlementLevel) -> core::result::Result<(), ()>;
+
+        /// Revoke `account`'s entitlement.
+        #[ink(message)]
+        fn revoke_entitlement(&mut self, account: Address) -> core::result::Result<(), ()>;
+    }
+
+    /// Mirrors `access_registry::EntitlementLevel`'s variant order, since
+    /// cross-contract calls are matched by SCALE layout rather than by name.
+    #[derive(Default, Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum EntitlementLevel {
+        #[default]
+        None,
+        Basic,
+        Premium,
+        Vip,
+    }
+
     /// Payment status
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
@@ -16,6 +58,32 @@ mod payment_integration {
         Completed,
         Failed,
         Refunded,
+        /// Reached via `reclaim_expired` once a `Completed` payment's
+        /// `expires_at` has passed.
+        Expired,
+    }
+
+    /// A release condition that must be satisfied before an escrowed
+    /// payment can transition to `Completed`, modeled on Solana's Budget
+    /// contract "payment plan" primitives.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum Condition {
+        /// Released once `Address` calls `apply_witness` as the approver.
+        Signature(Address),
+        /// Released once the block timestamp reaches this deadline.
+        Timestamp(u64),
+    }
+
+    /// Proof submitted to `apply_witness` that a [`Condition`] has been
+    /// satisfied. Carries no payload: a `Signature` witness is checked
+    /// against the caller, and a `Timestamp` witness against the current
+    /// block time.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Witness {
+        Signature,
+        Timestamp,
     }
 
     /// Payment record
@@ -29,6 +97,35 @@ mod payment_integration {
         pub entitlement_granted: u8, // Entitlement level granted
         pub status: PaymentStatus,
         pub timestamp: u64,
+        /// When set, this payment is an escrow: `complete_payment` refuses
+        /// it until `apply_witness` satisfies the condition and completes
+        /// it directly.
+        pub release_condition: Option<Condition>,
+        /// Number of times `retry_payment` has moved this payment back to
+        /// `Pending` after a `Failed` status.
+        pub retry_count: u8,
+        /// Cap on `retry_count` beyond which `retry_payment` refuses with
+        /// `RetriesExhausted`.
+        pub max_retries: u8,
+        /// When set, the entitlement granted by this payment lapses once
+        /// the block timestamp reaches this value: `reclaim_expired` moves
+        /// the payment to `Expired` and `is_entitlement_active` returns
+        /// `false`. Unset means the entitlement never expires on its own.
+        pub expires_at: Option<u64>,
+    }
+
+    /// One entry of a [`PaymentIntegration::record_payments_batch`] call,
+    /// mirroring [`PaymentIntegration::record_payment`]'s arguments.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct PaymentInput {
+        pub account: Address,
+        pub provider: String,
+        pub transaction_id: String,
+        pub amount: Balance,
+        pub entitlement_granted: u8,
+        pub release_condition: Option<Condition>,
+        pub expires_at: Option<u64>,
     }
 
     /// Payment integration contract for managing payments and entitlements
@@ -46,6 +143,11 @@ mod payment_integration {
         access_registry: Option<Address>,
         /// Authorized payment processors
         authorized_processors: Mapping<Address, bool>,
+        /// Mapping from account to every payment ID recorded for it, so
+        /// `refund_payment`/`reclaim_expired` can tell whether another
+        /// payment still justifies the account's `access_registry`
+        /// entitlement before revoking it.
+        account_payments: Mapping<Address, Vec<u32>>,
     }
 
     /// Events emitted by the contract
@@ -104,10 +206,32 @@ mod payment_integration {
         InvalidStatus,
         /// Input string exceeds maximum length
         InputTooLong,
+        /// The payment is escrowed and its release condition has not been
+        /// satisfied yet
+        ConditionNotMet,
+        /// The cross-contract call to `access_registry` failed or reverted
+        RegistryCallFailed,
+        /// `retry_payment` was called after `retry_count` already reached
+        /// `max_retries`
+        RetriesExhausted,
     }
 
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// Convert a raw `entitlement_granted` level into the [`EntitlementLevel`]
+    /// variant expected by `access_registry::grant_entitlement`. Unrecognized
+    /// values map to `None` rather than failing, since `entitlement_granted`
+    /// is stored as a bare `u8` for forward-compatibility with entitlement
+    /// levels this contract doesn't otherwise interpret.
+    fn entitlement_level(value: u8) -> EntitlementLevel {
+        match value {
+            1 => EntitlementLevel::Basic,
+            2 => EntitlementLevel::Premium,
+            3 => EntitlementLevel::Vip,
+            _ => EntitlementLevel::None,
+        }
+    }
+
     impl Default for PaymentIntegration {
         fn default() -> Self {
             Self::new()
@@ -126,13 +250,19 @@ mod payment_integration {
                 owner: caller,
                 access_registry: None,
                 authorized_processors: Mapping::default(),
+                account_payments: Mapping::default(),
             };
             // Owner is automatically an authorized processor
             contract.authorized_processors.insert(caller, &true);
             contract
         }
 
-        /// Set the access registry contract address
+        /// Set the access registry contract address.
+        ///
+        /// This contract's own address must separately be authorized on the
+        /// registry via its `authorize_integrator` message, or the calls
+        /// made from [`Self::complete_payment`] / [`Self::refund_payment`] /
+        /// [`Self::reclaim_expired`] will fail with `RegistryCallFailed`.
         #[ink(message)]
         pub fn set_access_registry(&mut self, address: Address) -> Result<()> {
             if self.env().caller() != self.owner {
@@ -156,8 +286,13 @@ mod payment_integration {
             Ok(())
         }
 
-        /// Record a new payment (pending status)
+        /// Record a new payment (pending status). When `release_condition`
+        /// is set, the payment is an escrow: `complete_payment` will refuse
+        /// it until `apply_witness` satisfies the condition. When
+        /// `expires_at` is set, the granted entitlement lapses at that
+        /// timestamp and becomes reclaimable via `reclaim_expired`.
         #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
         pub fn record_payment(
             &mut self,
             account: Address,
@@ -165,20 +300,14 @@ mod payment_integration {
             transaction_id: String,
             amount: Balance,
             entitlement_granted: u8,
+            release_condition: Option<Condition>,
+            expires_at: Option<u64>,
         ) -> Result<u32> {
             if !self.is_authorized_processor(self.env().caller()) {
                 return Err(Error::NotAuthorizedProcessor);
             }
 
-            // Validate input lengths
-            if provider.len() > MAX_STRING_LENGTH || transaction_id.len() > MAX_STRING_LENGTH {
-                return Err(Error::InputTooLong);
-            }
-
-            // Check if transaction already exists
-            if self.transaction_to_payment.contains(transaction_id.clone()) {
-                return Err(Error::PaymentAlreadyExists);
-            }
+            self.validate_new_payment(&provider, &transaction_id)?;
 
             let payment_id = self.next_payment_id;
             let timestamp = self.env().block_timestamp();
@@ -191,10 +320,15 @@ mod payment_integration {
                 entitlement_granted,
                 status: PaymentStatus::Pending,
                 timestamp,
+                release_condition,
+                retry_count: 0,
+                max_retries: DEFAULT_MAX_RETRIES,
+                expires_at,
             };
 
             self.payments.insert(payment_id, &payment);
             self.transaction_to_payment.insert(transaction_id.clone(), &payment_id);
+            self.push_account_payment(account, payment_id);
             self.next_payment_id += 1;
 
             self.env().emit_event(PaymentRecorded {
@@ -208,8 +342,145 @@ mod payment_integration {
             Ok(payment_id)
         }
 
-        /// Complete a payment and grant entitlement
-        /// In a real implementation, this would call the `access_registry` contract
+        /// Record many payments in a single call with all-or-nothing
+        /// semantics: every entry is validated up front (string lengths, no
+        /// duplicate `transaction_id` within the batch or against an
+        /// already-recorded payment) before any storage is mutated, so a
+        /// bad entry can't leave a partial batch behind. Inspired by
+        /// bundling multiple instructions into one atomic transaction for a
+        /// throughput gain over one call per payment.
+        #[ink(message)]
+        pub fn record_payments_batch(&mut self, payments: Vec<PaymentInput>) -> Result<Vec<u32>> {
+            if !self.is_authorized_processor(self.env().caller()) {
+                return Err(Error::NotAuthorizedProcessor);
+            }
+
+            let mut seen_transaction_ids: Vec<&str> = Vec::with_capacity(payments.len());
+            for input in &payments {
+                self.validate_new_payment(&input.provider, &input.transaction_id)?;
+
+                if seen_transaction_ids.contains(&input.transaction_id.as_str()) {
+                    return Err(Error::PaymentAlreadyExists);
+                }
+                seen_transaction_ids.push(&input.transaction_id);
+            }
+
+            let timestamp = self.env().block_timestamp();
+            let mut payment_ids = Vec::with_capacity(payments.len());
+
+            for input in payments {
+                let payment_id = self.next_payment_id;
+
+                let payment = Payment {
+                    account: input.account,
+                    provider: input.provider.clone(),
+                    transaction_id: input.transaction_id.clone(),
+                    amount: input.amount,
+                    entitlement_granted: input.entitlement_granted,
+                    status: PaymentStatus::Pending,
+                    timestamp,
+                    release_condition: input.release_condition,
+                    retry_count: 0,
+                    max_retries: DEFAULT_MAX_RETRIES,
+                    expires_at: input.expires_at,
+                };
+
+                self.payments.insert(payment_id, &payment);
+                self.transaction_to_payment
+                    .insert(input.transaction_id.clone(), &payment_id);
+                self.push_account_payment(input.account, payment_id);
+                self.next_payment_id += 1;
+
+                self.env().emit_event(PaymentRecorded {
+                    payment_id,
+                    account: input.account,
+                    payment_provider: input.provider,
+                    transaction_id: input.transaction_id,
+                    amount: input.amount,
+                });
+
+                payment_ids.push(payment_id);
+            }
+
+            Ok(payment_ids)
+        }
+
+        /// Validate a prospective payment's `provider`/`transaction_id`
+        /// against the length limit and existing transaction records,
+        /// shared by [`Self::record_payment`] and
+        /// [`Self::record_payments_batch`].
+        fn validate_new_payment(&self, provider: &String, transaction_id: &String) -> Result<()> {
+            if provider.len() > MAX_STRING_LENGTH || transaction_id.len() > MAX_STRING_LENGTH {
+                return Err(Error::InputTooLong);
+            }
+
+            if self.transaction_to_payment.contains(transaction_id.clone()) {
+                return Err(Error::PaymentAlreadyExists);
+            }
+
+            Ok(())
+        }
+
+        /// Record `payment_id` against `account` in [`Self::account_payments`].
+        fn push_account_payment(&mut self, account: Address, payment_id: u32) {
+            let mut ids = self.account_payments.get(account).unwrap_or_default();
+            ids.push(payment_id);
+            self.account_payments.insert(account, &ids);
+        }
+
+        /// Whether some payment for `account` other than `excluding_payment_id`
+        /// is still `Completed` and unexpired, i.e. still justifies the
+        /// account's `access_registry` entitlement on its own. Used by
+        /// [`Self::refund_payment`] and [`Self::reclaim_expired`] so revoking
+        /// one payment's entitlement doesn't clobber a second, still-active
+        /// grant for the same account (e.g. a renewed subscription).
+        fn other_payment_justifies_entitlement(&self, account: Address, excluding_payment_id: u32) -> bool {
+            let now = self.env().block_timestamp();
+            self.account_payments
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|&id| id != excluding_payment_id)
+                .filter_map(|id| self.payments.get(id))
+                .any(|payment| {
+                    if payment.status != PaymentStatus::Completed {
+                        return false;
+                    }
+                    match payment.expires_at {
+                        Some(expires_at) => now < expires_at,
+                        None => true,
+                    }
+                })
+        }
+
+        /// Grant `entitlement_granted` to `account` via the configured
+        /// `access_registry`'s `grant_entitlement` message, if one is set.
+        /// Shared by [`Self::complete_payment`] and [`Self::apply_witness`],
+        /// the two paths that transition a payment to `Completed`.
+        fn grant_entitlement_via_registry(
+            &self,
+            account: Address,
+            entitlement_granted: u8,
+        ) -> Result<()> {
+            if let Some(access_registry_addr) = self.access_registry {
+                let mut registry: ink::contract_ref!(AccessRegistry) = access_registry_addr.into();
+                registry
+                    .call_mut()
+                    .grant_entitlement(account, entitlement_level(entitlement_granted))
+                    .ref_time_limit(CROSS_CALL_REF_TIME_LIMIT)
+                    .proof_size_limit(CROSS_CALL_PROOF_SIZE_LIMIT)
+                    .storage_deposit_limit(CROSS_CALL_STORAGE_DEPOSIT_LIMIT)
+                    .try_invoke()
+                    .map_err(|_| Error::RegistryCallFailed)?
+                    .map_err(|_| Error::RegistryCallFailed)?;
+            }
+            Ok(())
+        }
+
+        /// Complete a payment and grant entitlement. When `access_registry`
+        /// is configured, this calls its `grant_entitlement` message first;
+        /// if that call fails or reverts, the payment's status is left
+        /// unchanged so it never diverges from the registry's state.
         #[ink(message)]
         pub fn complete_payment(&mut self, payment_id: u32) -> Result<()> {
             if !self.is_authorized_processor(self.env().caller()) {
@@ -222,11 +493,62 @@ mod payment_integration {
                 return Err(Error::InvalidStatus);
             }
 
+            if payment.release_condition.is_some() {
+                return Err(Error::ConditionNotMet);
+            }
+
+            self.grant_entitlement_via_registry(payment.account, payment.entitlement_granted)?;
+
             payment.status = PaymentStatus::Completed;
             self.payments.insert(payment_id, &payment);
 
-            // In a full implementation, this would call access_registry.grant_entitlement()
-            // For now, we just emit an event
+            self.env().emit_event(PaymentCompleted {
+                payment_id,
+                account: payment.account,
+                entitlement_granted: payment.entitlement_granted,
+            });
+
+            Ok(())
+        }
+
+        /// Submit proof that an escrowed payment's release condition has
+        /// been satisfied. A `Signature` witness is validated against
+        /// `self.env().caller()` matching the condition's approver; a
+        /// `Timestamp` witness is validated against the current block time
+        /// having reached the condition's deadline. Anyone may call this
+        /// (not just authorized processors), since the witness check itself
+        /// is the authorization. When the witness satisfies the stored
+        /// condition, entitlement is granted the same way as
+        /// [`Self::complete_payment`] and the payment completes with
+        /// `PaymentCompleted` firing; otherwise it stays `Pending`.
+        #[ink(message)]
+        pub fn apply_witness(&mut self, payment_id: u32, witness: Witness) -> Result<()> {
+            let mut payment = self.payments.get(payment_id).ok_or(Error::PaymentNotFound)?;
+
+            if payment.status != PaymentStatus::Pending {
+                return Err(Error::InvalidStatus);
+            }
+
+            let condition = payment.release_condition.clone().ok_or(Error::ConditionNotMet)?;
+
+            let satisfied = match (&condition, &witness) {
+                (Condition::Signature(approver), Witness::Signature) => {
+                    self.env().caller() == *approver
+                }
+                (Condition::Timestamp(deadline), Witness::Timestamp) => {
+                    self.env().block_timestamp() >= *deadline
+                }
+                _ => false,
+            };
+
+            if !satisfied {
+                return Err(Error::ConditionNotMet);
+            }
+
+            self.grant_entitlement_via_registry(payment.account, payment.entitlement_granted)?;
+
+            payment.status = PaymentStatus::Completed;
+            self.payments.insert(payment_id, &payment);
 
             self.env().emit_event(PaymentCompleted {
                 payment_id,
@@ -237,7 +559,10 @@ mod payment_integration {
             Ok(())
         }
 
-        /// Mark a payment as failed
+        /// Mark a payment as failed. Only a `Pending` payment can fail, so
+        /// this can't be used to knock a `Completed`/`Refunded` payment back
+        /// into the `Failed` -> `retry_payment` -> `Pending` cycle and
+        /// re-trigger entitlement granting.
         #[ink(message)]
         pub fn fail_payment(&mut self, payment_id: u32, reason: String) -> Result<()> {
             if !self.is_authorized_processor(self.env().caller()) {
@@ -246,6 +571,10 @@ mod payment_integration {
 
             let mut payment = self.payments.get(payment_id).ok_or(Error::PaymentNotFound)?;
 
+            if payment.status != PaymentStatus::Pending {
+                return Err(Error::InvalidStatus);
+            }
+
             payment.status = PaymentStatus::Failed;
             self.payments.insert(payment_id, &payment);
 
@@ -254,7 +583,46 @@ mod payment_integration {
             Ok(())
         }
 
-        /// Refund a payment
+        /// Re-attempt a `Failed` payment by moving it back to `Pending` and
+        /// re-emitting `PaymentRecorded`, so a transient provider failure
+        /// can be retried without losing the original transaction record.
+        /// Refuses once `retry_count` reaches `max_retries`.
+        #[ink(message)]
+        pub fn retry_payment(&mut self, payment_id: u32) -> Result<()> {
+            if !self.is_authorized_processor(self.env().caller()) {
+                return Err(Error::NotAuthorizedProcessor);
+            }
+
+            let mut payment = self.payments.get(payment_id).ok_or(Error::PaymentNotFound)?;
+
+            if payment.status != PaymentStatus::Failed {
+                return Err(Error::InvalidStatus);
+            }
+
+            if payment.retry_count >= payment.max_retries {
+                return Err(Error::RetriesExhausted);
+            }
+
+            payment.retry_count += 1;
+            payment.status = PaymentStatus::Pending;
+            self.payments.insert(payment_id, &payment);
+
+            self.env().emit_event(PaymentRecorded {
+                payment_id,
+                account: payment.account,
+                payment_provider: payment.provider,
+                transaction_id: payment.transaction_id,
+                amount: payment.amount,
+            });
+
+            Ok(())
+        }
+
+        /// Refund a payment. When `access_registry` is configured and no
+        /// other `Completed`, unexpired payment for the same account still
+        /// justifies its entitlement, this calls `revoke_entitlement` first;
+        /// if that call fails or reverts, the payment's status is left
+        /// unchanged so it never diverges from the registry's state.
         #[ink(message)]
         pub fn refund_payment(&mut self, payment_id: u32) -> Result<()> {
             if !self.is_authorized_processor(self.env().caller()) {
@@ -267,16 +635,113 @@ mod payment_integration {
                 return Err(Error::InvalidStatus);
             }
 
+            if !self.other_payment_justifies_entitlement(payment.account, payment_id) {
+                if let Some(access_registry_addr) = self.access_registry {
+                    let mut registry: ink::contract_ref!(AccessRegistry) = access_registry_addr.into();
+                    registry
+                        .call_mut()
+                        .revoke_entitlement(payment.account)
+                        .ref_time_limit(CROSS_CALL_REF_TIME_LIMIT)
+                        .proof_size_limit(CROSS_CALL_PROOF_SIZE_LIMIT)
+                        .storage_deposit_limit(CROSS_CALL_STORAGE_DEPOSIT_LIMIT)
+                        .try_invoke()
+                        .map_err(|_| Error::RegistryCallFailed)?
+                        .map_err(|_| Error::RegistryCallFailed)?;
+                }
+            }
+
             payment.status = PaymentStatus::Refunded;
             self.payments.insert(payment_id, &payment);
 
-            // In a full implementation, this would call access_registry.revoke_entitlement()
-
             self.env().emit_event(PaymentRefunded { payment_id });
 
             Ok(())
         }
 
+        /// Sweep `payment_ids`, transitioning every `Completed` payment
+        /// whose `expires_at` the block timestamp has reached to `Expired` and revoking its
+        /// entitlement via `access_registry` (when configured), unless
+        /// another `Completed`, unexpired payment for the same account
+        /// still justifies it. Entries that aren't found, aren't
+        /// `Completed`, have no `expires_at`, or haven't expired yet are
+        /// left untouched rather than erroring, so one sweep call can cover
+        /// a mixed batch. Returns the IDs actually reclaimed.
+        #[ink(message)]
+        pub fn reclaim_expired(&mut self, payment_ids: Vec<u32>) -> Result<Vec<u32>> {
+            if !self.is_authorized_processor(self.env().caller()) {
+                return Err(Error::NotAuthorizedProcessor);
+            }
+
+            let now = self.env().block_timestamp();
+            let mut reclaimed = Vec::new();
+
+            for payment_id in payment_ids {
+                let mut payment = match self.payments.get(payment_id) {
+                    Some(payment) => payment,
+                    None => continue,
+                };
+
+                if payment.status != PaymentStatus::Completed {
+                    continue;
+                }
+
+                let expires_at = match payment.expires_at {
+                    Some(expires_at) => expires_at,
+                    None => continue,
+                };
+
+                if now < expires_at {
+                    continue;
+                }
+
+                let still_justified =
+                    self.other_payment_justifies_entitlement(payment.account, payment_id);
+
+                if !still_justified {
+                    if let Some(access_registry_addr) = self.access_registry {
+                        let mut registry: ink::contract_ref!(AccessRegistry) =
+                            access_registry_addr.into();
+                        let revoked = registry
+                            .call_mut()
+                            .revoke_entitlement(payment.account)
+                            .ref_time_limit(CROSS_CALL_REF_TIME_LIMIT)
+                            .proof_size_limit(CROSS_CALL_PROOF_SIZE_LIMIT)
+                            .storage_deposit_limit(CROSS_CALL_STORAGE_DEPOSIT_LIMIT)
+                            .try_invoke();
+
+                        if !matches!(revoked, Ok(Ok(()))) {
+                            continue;
+                        }
+                    }
+                }
+
+                payment.status = PaymentStatus::Expired;
+                self.payments.insert(payment_id, &payment);
+                reclaimed.push(payment_id);
+            }
+
+            Ok(reclaimed)
+        }
+
+        /// True only for a `Completed` payment whose `expires_at` is still
+        /// later than the block timestamp (or that has none).
+        #[ink(message)]
+        pub fn is_entitlement_active(&self, payment_id: u32) -> bool {
+            let payment = match self.payments.get(payment_id) {
+                Some(payment) => payment,
+                None => return false,
+            };
+
+            if payment.status != PaymentStatus::Completed {
+                return false;
+            }
+
+            match payment.expires_at {
+                Some(expires_at) => self.env().block_timestamp() < expires_at,
+                None => true,
+            }
+        }
+
         /// Get payment details
         #[ink(message)]
         pub fn get_payment(&self, payment_id: u32) -> Option<Payment> {
@@ -289,6 +754,12 @@ mod payment_integration {
             self.transaction_to_payment.get(transaction_id)
         }
 
+        /// Get how many times `retry_payment` has been used on a payment
+        #[ink(message)]
+        pub fn get_retry_count(&self, payment_id: u32) -> Option<u8> {
+            self.payments.get(payment_id).map(|payment| payment.retry_count)
+        }
+
         /// Check if an account is an authorized processor
         #[ink(message)]
         pub fn is_authorized_processor(&self, account: Address) -> bool {
@@ -333,6 +804,8 @@ mod payment_integration {
                     String::from("txn-123"),
                     1000,
                     2,
+                    None,
+                    None,
                 )
                 .unwrap();
 
@@ -355,6 +828,8 @@ mod payment_integration {
                     String::from("txn-123"),
                     1000,
                     2,
+                    None,
+                    None,
                 )
                 .unwrap();
 
@@ -376,6 +851,8 @@ mod payment_integration {
                     String::from("txn-123"),
                     1000,
                     2,
+                    None,
+                    None,
                 )
                 .unwrap();
 
@@ -386,6 +863,42 @@ mod payment_integration {
             assert_eq!(payment.status, PaymentStatus::Refunded);
         }
 
+        #[ink::test]
+        fn other_payment_justifies_entitlement_true_with_second_active_payment() {
+            // Two Completed payments for the same account (e.g. a renewed
+            // subscription): refunding/reclaiming one must not report that
+            // the account's entitlement is unjustified while the other is
+            // still active.
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_a = contract
+                .record_payment(account, String::from("apple"), String::from("txn-a"), 1000, 2, None, None)
+                .unwrap();
+            let payment_b = contract
+                .record_payment(account, String::from("apple"), String::from("txn-b"), 1000, 2, None, None)
+                .unwrap();
+
+            contract.complete_payment(payment_a).unwrap();
+            contract.complete_payment(payment_b).unwrap();
+
+            assert!(contract.other_payment_justifies_entitlement(account, payment_a));
+            assert!(contract.other_payment_justifies_entitlement(account, payment_b));
+        }
+
+        #[ink::test]
+        fn other_payment_justifies_entitlement_false_with_no_other_active_payment() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_id = contract
+                .record_payment(account, String::from("apple"), String::from("txn-123"), 1000, 2, None, None)
+                .unwrap();
+            contract.complete_payment(payment_id).unwrap();
+
+            assert!(!contract.other_payment_justifies_entitlement(account, payment_id));
+        }
+
         #[ink::test]
         fn authorize_processor_works() {
             let mut contract = PaymentIntegration::new();
@@ -394,5 +907,322 @@ mod payment_integration {
             assert!(contract.authorize_processor(processor).is_ok());
             assert!(contract.is_authorized_processor(processor));
         }
+
+        #[ink::test]
+        fn complete_payment_rejects_unmet_escrow() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+            let approver = Address::from([0x04; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    Some(Condition::Signature(approver)),
+                    None,
+                )
+                .unwrap();
+
+            assert_eq!(
+                contract.complete_payment(payment_id),
+                Err(Error::ConditionNotMet)
+            );
+        }
+
+        #[ink::test]
+        fn apply_witness_completes_on_matching_signature() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+            let approver = Address::from([0x04; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    Some(Condition::Signature(approver)),
+                    None,
+                )
+                .unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(approver);
+            assert!(contract.apply_witness(payment_id, Witness::Signature).is_ok());
+
+            let payment = contract.get_payment(payment_id).unwrap();
+            assert_eq!(payment.status, PaymentStatus::Completed);
+        }
+
+        #[ink::test]
+        fn apply_witness_rejects_wrong_signer() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+            let approver = Address::from([0x04; 20]);
+            let impostor = Address::from([0x05; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    Some(Condition::Signature(approver)),
+                    None,
+                )
+                .unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(impostor);
+            assert_eq!(
+                contract.apply_witness(payment_id, Witness::Signature),
+                Err(Error::ConditionNotMet)
+            );
+
+            let payment = contract.get_payment(payment_id).unwrap();
+            assert_eq!(payment.status, PaymentStatus::Pending);
+        }
+
+        #[ink::test]
+        fn apply_witness_completes_once_deadline_reached() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+            // A deadline of 0 is always reached, regardless of the test
+            // environment's current block timestamp.
+            let deadline = 0u64;
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    Some(Condition::Timestamp(deadline)),
+                    None,
+                )
+                .unwrap();
+
+            assert!(contract.apply_witness(payment_id, Witness::Timestamp).is_ok());
+
+            let payment = contract.get_payment(payment_id).unwrap();
+            assert_eq!(payment.status, PaymentStatus::Completed);
+        }
+
+        #[ink::test]
+        fn record_payments_batch_works() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_ids = contract
+                .record_payments_batch(vec![
+                    PaymentInput {
+                        account,
+                        provider: String::from("apple"),
+                        transaction_id: String::from("txn-1"),
+                        amount: 1000,
+                        entitlement_granted: 2,
+                        release_condition: None,
+                        expires_at: None,
+                    },
+                    PaymentInput {
+                        account,
+                        provider: String::from("google"),
+                        transaction_id: String::from("txn-2"),
+                        amount: 2000,
+                        entitlement_granted: 3,
+                        release_condition: None,
+                        expires_at: None,
+                    },
+                ])
+                .unwrap();
+
+            assert_eq!(payment_ids, vec![0, 1]);
+            assert_eq!(
+                contract.get_payment(0).unwrap().transaction_id,
+                "txn-1"
+            );
+            assert_eq!(
+                contract.get_payment(1).unwrap().transaction_id,
+                "txn-2"
+            );
+        }
+
+        #[ink::test]
+        fn record_payments_batch_rejects_duplicate_without_mutating_storage() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let result = contract.record_payments_batch(vec![
+                PaymentInput {
+                    account,
+                    provider: String::from("apple"),
+                    transaction_id: String::from("txn-dup"),
+                    amount: 1000,
+                    entitlement_granted: 2,
+                    release_condition: None,
+                    expires_at: None,
+                },
+                PaymentInput {
+                    account,
+                    provider: String::from("google"),
+                    transaction_id: String::from("txn-dup"),
+                    amount: 2000,
+                    entitlement_granted: 3,
+                    release_condition: None,
+                    expires_at: None,
+                },
+            ]);
+
+            assert_eq!(result, Err(Error::PaymentAlreadyExists));
+            assert_eq!(contract.next_payment_id(), 0);
+            assert_eq!(contract.get_payment_by_transaction(String::from("txn-dup")), None);
+        }
+
+        #[ink::test]
+        fn fail_payment_rejects_non_pending_payments() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    None,
+                    None,
+                )
+                .unwrap();
+
+            contract.complete_payment(payment_id).unwrap();
+
+            assert_eq!(
+                contract.fail_payment(payment_id, String::from("too late")),
+                Err(Error::InvalidStatus)
+            );
+            assert_eq!(
+                contract.get_payment(payment_id).unwrap().status,
+                PaymentStatus::Completed
+            );
+        }
+
+        #[ink::test]
+        fn retry_payment_moves_failed_back_to_pending() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    None,
+                    None,
+                )
+                .unwrap();
+
+            contract.fail_payment(payment_id, String::from("provider timeout")).unwrap();
+
+            assert!(contract.retry_payment(payment_id).is_ok());
+            assert_eq!(
+                contract.get_payment(payment_id).unwrap().status,
+                PaymentStatus::Pending
+            );
+            assert_eq!(contract.get_retry_count(payment_id), Some(1));
+        }
+
+        #[ink::test]
+        fn retry_payment_rejects_once_max_retries_reached() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    None,
+                    None,
+                )
+                .unwrap();
+
+            for _ in 0..DEFAULT_MAX_RETRIES {
+                contract.fail_payment(payment_id, String::from("provider timeout")).unwrap();
+                contract.retry_payment(payment_id).unwrap();
+            }
+
+            contract.fail_payment(payment_id, String::from("provider timeout")).unwrap();
+            assert_eq!(
+                contract.retry_payment(payment_id),
+                Err(Error::RetriesExhausted)
+            );
+        }
+
+        #[ink::test]
+        fn reclaim_expired_transitions_past_deadline_payments() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    None,
+                    Some(0),
+                )
+                .unwrap();
+            contract.complete_payment(payment_id).unwrap();
+
+            assert!(!contract.is_entitlement_active(payment_id));
+
+            let reclaimed = contract.reclaim_expired(vec![payment_id]).unwrap();
+
+            assert_eq!(reclaimed, vec![payment_id]);
+            assert_eq!(
+                contract.get_payment(payment_id).unwrap().status,
+                PaymentStatus::Expired
+            );
+        }
+
+        #[ink::test]
+        fn reclaim_expired_skips_payments_not_yet_expired() {
+            let mut contract = PaymentIntegration::new();
+            let account = Address::from([0x02; 20]);
+
+            let payment_id = contract
+                .record_payment(
+                    account,
+                    String::from("apple"),
+                    String::from("txn-123"),
+                    1000,
+                    2,
+                    None,
+                    Some(u64::MAX),
+                )
+                .unwrap();
+            contract.complete_payment(payment_id).unwrap();
+
+            assert!(contract.is_entitlement_active(payment_id));
+
+            let reclaimed = contract.reclaim_expired(vec![payment_id]).unwrap();
+
+            assert!(reclaimed.is_empty());
+            assert_eq!(
+                contract.get_payment(payment_id).unwrap().status,
+                PaymentStatus::Completed
+            );
+        }
     }
 }
@@ -11,6 +11,48 @@ mod policy_engine {
     /// Maximum number of required attributes in a policy
     const MAX_ATTRIBUTES: usize = 50;
 
+    /// `ref_time` weight limit applied to each cross-contract call, so a
+    /// misbehaving `access_registry`/`attribute_store` cannot exhaust the
+    /// caller's gas.
+    const CROSS_CALL_REF_TIME_LIMIT: u64 = 5_000_000_000;
+    /// `proof_size` weight limit applied to each cross-contract call.
+    const CROSS_CALL_PROOF_SIZE_LIMIT: u64 = 1_000_000;
+    /// Storage deposit limit applied to each cross-contract call. Both
+    /// configured contracts are read-only from `policy_engine`'s
+    /// perspective, so no deposit should ever be required.
+    const CROSS_CALL_STORAGE_DEPOSIT_LIMIT: Balance = 0;
+
+    /// Cross-contract interface implemented by the configured
+    /// `access_registry`.
+    #[ink::trait_definition]
+    pub trait AccessRegistry {
+        /// Return `account`'s current entitlement level.
+        #[ink(message)]
+        fn get_entitlement(&self, account: Address) -> EntitlementLevel;
+    }
+
+    /// Cross-contract interface implemented by the configured
+    /// `attribute_store`.
+    #[ink::trait_definition]
+    pub trait AttributeStore {
+        /// Return the value stored for `account` under `namespace_key`
+        /// (e.g. `"opentdf.role"`), if any.
+        #[ink(message)]
+        fn get_attribute(&self, account: Address, namespace_key: String) -> Option<String>;
+    }
+
+    /// Mirrors `access_registry::EntitlementLevel`'s variant order, since
+    /// cross-contract calls are matched by SCALE layout rather than by name.
+    #[derive(Default, Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum EntitlementLevel {
+        #[default]
+        None,
+        Basic,
+        Premium,
+        Vip,
+    }
+
     /// Policy rule for access control
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
@@ -34,6 +76,10 @@ mod policy_engine {
         access_registry: Option<Address>,
         /// Attribute store contract address
         attribute_store: Option<Address>,
+        /// Storage-layout version, bumped on each `set_code_hash` upgrade so
+        /// a post-upgrade migration step can detect which records still
+        /// need reconciling.
+        version: u32,
     }
 
     /// Events emitted by the contract
@@ -89,6 +135,11 @@ mod policy_engine {
         InputTooLong,
         /// Too many attributes in policy
         TooManyAttributes,
+        /// The runtime rejected the `set_code_hash` call
+        SetCodeHashFailed,
+        /// A cross-contract call to `access_registry` or `attribute_store`
+        /// failed or reverted
+        CrossContractCallFailed,
     }
 
     pub type Result<T> = core::result::Result<T, Error>;
@@ -109,6 +160,7 @@ mod policy_engine {
                 owner: Self::env().caller(),
                 access_registry: None,
                 attribute_store: None,
+                version: 1,
             }
         }
 
@@ -236,35 +288,115 @@ mod policy_engine {
             self.policies.get(policy_id)
         }
 
-        /// Evaluate access for an account against a policy
-        /// Note: In a real implementation, this would call the `access_registry`
-        /// and `attribute_store` contracts. For now, it's a simplified version.
+        /// Evaluate access for an account against a policy.
+        ///
+        /// Requires both `access_registry` and `attribute_store` to be
+        /// configured: checks the account's entitlement level via a
+        /// cross-contract call to `access_registry`, then checks every
+        /// `(namespace.key, value)` pair in the policy's
+        /// `required_attributes` via `attribute_store`, each call bounded by
+        /// an explicit weight and storage-deposit limit.
         #[ink(message)]
-        pub fn evaluate_access(&self, account: Address, policy_id: u32) -> bool {
-            if let Some(policy) = self.policies.get(policy_id) {
-                if !policy.active {
+        pub fn evaluate_access(&self, account: Address, policy_id: u32) -> Result<bool> {
+            let policy = self.policies.get(policy_id).ok_or(Error::PolicyNotFound)?;
+
+            if !policy.active {
+                self.env().emit_event(AccessDenied {
+                    account,
+                    policy_id,
+                    resource_id: policy.resource_id.clone(),
+                    reason: String::from("Policy inactive"),
+                });
+                return Ok(false);
+            }
+
+            let access_registry_addr = self
+                .access_registry
+                .ok_or(Error::ContractNotConfigured)?;
+            let attribute_store_addr = self
+                .attribute_store
+                .ok_or(Error::ContractNotConfigured)?;
+
+            let registry: ink::contract_ref!(AccessRegistry) = access_registry_addr.into();
+            let entitlement = registry
+                .call()
+                .get_entitlement(account)
+                .ref_time_limit(CROSS_CALL_REF_TIME_LIMIT)
+                .proof_size_limit(CROSS_CALL_PROOF_SIZE_LIMIT)
+                .storage_deposit_limit(CROSS_CALL_STORAGE_DEPOSIT_LIMIT)
+                .try_invoke()
+                .map_err(|_| Error::CrossContractCallFailed)?
+                .map_err(|_| Error::CrossContractCallFailed)?;
+
+            let store: ink::contract_ref!(AttributeStore) = attribute_store_addr.into();
+            let mut resolved_attributes = Vec::with_capacity(policy.required_attributes.len());
+            for (key, expected_value) in &policy.required_attributes {
+                let actual_value = store
+                    .call()
+                    .get_attribute(account, key.clone())
+                    .ref_time_limit(CROSS_CALL_REF_TIME_LIMIT)
+                    .proof_size_limit(CROSS_CALL_PROOF_SIZE_LIMIT)
+                    .storage_deposit_limit(CROSS_CALL_STORAGE_DEPOSIT_LIMIT)
+                    .try_invoke()
+                    .map_err(|_| Error::CrossContractCallFailed)?
+                    .map_err(|_| Error::CrossContractCallFailed)?;
+                resolved_attributes.push((key.clone(), expected_value.clone(), actual_value));
+            }
+
+            Ok(self.evaluate_access_with(account, policy_id, &policy, entitlement, &resolved_attributes))
+        }
+
+        /// Core of [`Self::evaluate_access`], parameterized over an already
+        /// resolved entitlement level and attribute values so the
+        /// comparison logic can be exercised without live
+        /// `access_registry`/`attribute_store` cross-contract calls (e.g. in
+        /// unit tests).
+        fn evaluate_access_with(
+            &self,
+            account: Address,
+            policy_id: u32,
+            policy: &PolicyRule,
+            entitlement: EntitlementLevel,
+            resolved_attributes: &[(String, String, Option<String>)],
+        ) -> bool {
+            if Self::level_value(entitlement) < policy.min_entitlement {
+                self.env().emit_event(AccessDenied {
+                    account,
+                    policy_id,
+                    resource_id: policy.resource_id.clone(),
+                    reason: String::from("Insufficient entitlement"),
+                });
+                return false;
+            }
+
+            for (_key, expected_value, actual_value) in resolved_attributes {
+                if actual_value.as_ref() != Some(expected_value) {
                     self.env().emit_event(AccessDenied {
                         account,
                         policy_id,
                         resource_id: policy.resource_id.clone(),
-                        reason: String::from("Policy inactive"),
+                        reason: String::from("Attribute mismatch"),
                     });
                     return false;
                 }
+            }
 
-                // In a full implementation, this would:
-                // 1. Call access_registry to check entitlement level
-                // 2. Call attribute_store to verify required attributes
-                // For now, we just emit an event
+            self.env().emit_event(AccessGranted {
+                account,
+                policy_id,
+                resource_id: policy.resource_id.clone(),
+            });
+            true
+        }
 
-                self.env().emit_event(AccessGranted {
-                    account,
-                    policy_id,
-                    resource_id: policy.resource_id,
-                });
-                true
-            } else {
-                false
+        /// Convert an [`EntitlementLevel`] into its ordinal value for
+        /// comparison against `PolicyRule::min_entitlement`.
+        fn level_value(level: EntitlementLevel) -> u8 {
+            match level {
+                EntitlementLevel::None => 0,
+                EntitlementLevel::Basic => 1,
+                EntitlementLevel::Premium => 2,
+                EntitlementLevel::Vip => 3,
             }
         }
 
@@ -279,6 +411,31 @@ mod policy_engine {
         pub fn next_policy_id(&self) -> u32 {
             self.next_policy_id
         }
+
+        /// Get the storage-layout version, bumped on each `set_code_hash`
+        /// upgrade.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            self.version
+        }
+
+        /// Upgrade this contract's code in place, preserving its storage
+        /// (the `Mapping<u32, PolicyRule>` is keyed independently of code
+        /// and survives unchanged). Bumps `version` so a post-upgrade
+        /// migration step can detect and reconcile old records.
+        #[ink(message)]
+        pub fn set_code_hash(&mut self, new_code_hash: Hash) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            self.env()
+                .set_code_hash(&new_code_hash)
+                .map_err(|_| Error::SetCodeHashFailed)?;
+            self.version += 1;
+
+            Ok(())
+        }
     }
 
     #[cfg(test)]
@@ -291,6 +448,21 @@ mod policy_engine {
             // Owner is set to the default caller (zero address in test env)
             assert_eq!(contract.owner(), Address::default());
             assert_eq!(contract.next_policy_id(), 0);
+            assert_eq!(contract.version(), 1);
+        }
+
+        #[ink::test]
+        fn set_code_hash_rejects_non_owner() {
+            let mut contract = PolicyEngine::new();
+            let non_owner = Address::from([0x09; 20]);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(non_owner);
+
+            assert_eq!(
+                contract.set_code_hash(Hash::from([0x11; 32])),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(contract.version(), 1);
         }
 
         #[ink::test]
@@ -352,7 +524,7 @@ mod policy_engine {
         }
 
         #[ink::test]
-        fn evaluate_access_works() {
+        fn evaluate_access_fails_without_contracts_configured() {
             let mut contract = PolicyEngine::new();
             let account = Address::from([0x02; 20]);
             let policy_id = contract
@@ -363,7 +535,102 @@ mod policy_engine {
                 )
                 .unwrap();
 
-            assert!(contract.evaluate_access(account, policy_id));
+            assert_eq!(
+                contract.evaluate_access(account, policy_id),
+                Err(Error::ContractNotConfigured)
+            );
+        }
+
+        #[ink::test]
+        fn evaluate_access_denies_inactive_policy_without_cross_contract_calls() {
+            let mut contract = PolicyEngine::new();
+            let account = Address::from([0x02; 20]);
+            let policy_id = contract
+                .create_policy(String::from("resource-123"), ink::prelude::vec![], 1)
+                .unwrap();
+            contract
+                .update_policy(policy_id, ink::prelude::vec![], 1, false)
+                .unwrap();
+
+            // Inactive policies are rejected before either cross-contract
+            // call is made, so this succeeds even with nothing configured.
+            assert_eq!(contract.evaluate_access(account, policy_id), Ok(false));
+        }
+
+        #[ink::test]
+        fn evaluate_access_with_grants_when_entitlement_and_attributes_match() {
+            let contract = PolicyEngine::new();
+            let account = Address::from([0x02; 20]);
+            let policy = PolicyRule {
+                resource_id: String::from("resource-123"),
+                required_attributes: ink::prelude::vec![(
+                    String::from("opentdf.role"),
+                    String::from("admin"),
+                )],
+                min_entitlement: 2,
+                active: true,
+            };
+            let resolved = ink::prelude::vec![(
+                String::from("opentdf.role"),
+                String::from("admin"),
+                Some(String::from("admin")),
+            )];
+
+            assert!(contract.evaluate_access_with(
+                account,
+                0,
+                &policy,
+                EntitlementLevel::Premium,
+                &resolved,
+            ));
+        }
+
+        #[ink::test]
+        fn evaluate_access_with_denies_insufficient_entitlement() {
+            let contract = PolicyEngine::new();
+            let account = Address::from([0x02; 20]);
+            let policy = PolicyRule {
+                resource_id: String::from("resource-123"),
+                required_attributes: ink::prelude::vec![],
+                min_entitlement: 2,
+                active: true,
+            };
+
+            assert!(!contract.evaluate_access_with(
+                account,
+                0,
+                &policy,
+                EntitlementLevel::Basic,
+                &[],
+            ));
+        }
+
+        #[ink::test]
+        fn evaluate_access_with_denies_attribute_mismatch() {
+            let contract = PolicyEngine::new();
+            let account = Address::from([0x02; 20]);
+            let policy = PolicyRule {
+                resource_id: String::from("resource-123"),
+                required_attributes: ink::prelude::vec![(
+                    String::from("opentdf.role"),
+                    String::from("admin"),
+                )],
+                min_entitlement: 0,
+                active: true,
+            };
+            let resolved = ink::prelude::vec![(
+                String::from("opentdf.role"),
+                String::from("admin"),
+                Some(String::from("viewer")),
+            )];
+
+            assert!(!contract.evaluate_access_with(
+                account,
+                0,
+                &policy,
+                EntitlementLevel::Vip,
+                &resolved,
+            ));
         }
     }
 }
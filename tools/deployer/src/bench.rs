@@ -0,0 +1,130 @@
+//! Persists and diffs gas/storage-deposit measurements across `bench` runs,
+//! so a regression in a contract's weight shows up as a readable delta
+//! instead of requiring a human to compare two raw JSON files.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// One message's dry-run measurement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasEntry {
+    pub label: String,
+    pub ref_time: u64,
+    pub proof_size: u64,
+    pub storage_deposit: u128,
+}
+
+/// All measurements taken for one contract in a single `bench` run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractReport {
+    pub contract: String,
+    pub entries: Vec<GasEntry>,
+}
+
+/// The full `gas_report.json` document: one [`ContractReport`] per
+/// benchmarked contract.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Report {
+    pub contracts: Vec<ContractReport>,
+}
+
+/// Load a previous `gas_report.json`, if one exists at `path`. Returns
+/// `Ok(None)` rather than an error when the file is simply missing, since
+/// the first `bench` run in a fresh checkout has nothing to diff against.
+pub fn load_report(path: &Path) -> Result<Option<Report>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read previous gas report at {:?}", path))?;
+    let report = serde_json::from_str(&raw)
+        .with_context(|| format!("Failed to parse previous gas report at {:?}", path))?;
+
+    Ok(Some(report))
+}
+
+/// Write `report` to `path` as pretty-printed JSON.
+pub fn write_report(path: &Path, report: &Report) -> Result<()> {
+    let json = serde_json::to_string_pretty(report).context("Failed to serialize gas report")?;
+    fs::write(path, json).with_context(|| format!("Failed to write gas report to {:?}", path))
+}
+
+/// Log a human-readable delta between `previous` and `current` for every
+/// entry in `current`, so a weight regression is visible in CI output
+/// without anyone diffing the JSON by hand.
+pub fn print_diff(previous: Option<&Report>, current: &Report) {
+    for contract_report in &current.contracts {
+        let previous_entries = previous
+            .and_then(|report| {
+                report
+                    .contracts
+                    .iter()
+                    .find(|c| c.contract == contract_report.contract)
+            })
+            .map(|c| c.entries.as_slice())
+            .unwrap_or(&[]);
+
+        for entry in &contract_report.entries {
+            match previous_entries.iter().find(|e| e.label == entry.label) {
+                Some(previous_entry) => {
+                    let ref_time_delta = signed_delta(previous_entry.ref_time, entry.ref_time);
+                    let proof_size_delta = signed_delta(previous_entry.proof_size, entry.proof_size);
+                    let storage_deposit_delta =
+                        signed_delta_u128(previous_entry.storage_deposit, entry.storage_deposit);
+
+                    info!(
+                        "{}::{}: ref_time={} ({}), proof_size={} ({}), storage_deposit={} ({})",
+                        contract_report.contract,
+                        entry.label,
+                        entry.ref_time,
+                        ref_time_delta,
+                        entry.proof_size,
+                        proof_size_delta,
+                        entry.storage_deposit,
+                        storage_deposit_delta,
+                    );
+                }
+                None => {
+                    info!(
+                        "{}::{}: ref_time={}, proof_size={}, storage_deposit={} (new)",
+                        contract_report.contract, entry.label, entry.ref_time, entry.proof_size, entry.storage_deposit,
+                    );
+                }
+            }
+        }
+
+        for previous_entry in previous_entries {
+            if !contract_report
+                .entries
+                .iter()
+                .any(|e| e.label == previous_entry.label)
+            {
+                warn!(
+                    "{}::{}: present in the previous gas report but not measured this run",
+                    contract_report.contract, previous_entry.label
+                );
+            }
+        }
+    }
+}
+
+/// Format `current - previous` as a signed, human-readable delta.
+fn signed_delta(previous: u64, current: u64) -> String {
+    if current >= previous {
+        format!("+{}", current - previous)
+    } else {
+        format!("-{}", previous - current)
+    }
+}
+
+fn signed_delta_u128(previous: u128, current: u128) -> String {
+    if current >= previous {
+        format!("+{}", current - previous)
+    } else {
+        format!("-{}", previous - current)
+    }
+}
@@ -1,11 +1,21 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use futures::StreamExt;
+use scale::{Decode, Encode};
 use sp_core::sr25519::Pair;
+use sp_core::H256;
 use sp_keyring::AccountKeyring;
 use std::path::PathBuf;
+use subxt::dynamic::Value;
+use subxt::tx::{PairSigner, Signer};
 use subxt::{OnlineClient, PolkadotConfig};
 use tracing::{info, warn};
 
+mod bench;
+mod metadata;
+
+type DeploySigner = PairSigner<PolkadotConfig, Pair>;
+
 #[derive(Parser)]
 #[command(name = "deployer")]
 #[command(about = "Deploy and manage Ink! smart contracts on Arkavo Node", long_about = None)]
@@ -29,6 +39,10 @@ enum Commands {
         /// Account to use for upload (Alice, Bob, etc.)
         #[arg(short, long, default_value = "alice")]
         account: String,
+
+        /// Storage deposit limit for the upload, in plancks
+        #[arg(long)]
+        storage_deposit_limit: Option<u128>,
     },
 
     /// Instantiate a contract
@@ -37,22 +51,58 @@ enum Commands {
         #[arg(short, long)]
         code_hash: String,
 
-        /// Constructor selector (hex)
+        /// Constructor selector (hex). Ignored when `--metadata` and
+        /// `--constructor` are both given.
         #[arg(short = 's', long, default_value = "0x9bae9d5e")]
         selector: String,
 
-        /// Constructor arguments (hex)
+        /// Constructor arguments (hex). Ignored when `--metadata` and
+        /// `--constructor` are both given.
         #[arg(short, long, default_value = "")]
         args: String,
 
+        /// Path to the contract's `.contract`/metadata `.json` bundle. When
+        /// set together with `--constructor`, the selector and arguments
+        /// are resolved from the ABI by name instead of `--selector`/`--args`.
+        #[arg(long)]
+        metadata: Option<PathBuf>,
+
+        /// Constructor name to look up in `--metadata` (e.g. `new`).
+        #[arg(long)]
+        constructor: Option<String>,
+
+        /// A constructor argument as `label=value`, repeatable. Only used
+        /// together with `--metadata`/`--constructor`.
+        #[arg(long = "arg")]
+        metadata_args: Vec<String>,
+
         /// Initial balance to transfer to the contract
         #[arg(short, long, default_value = "0")]
         value: u128,
 
-        /// Gas limit
+        /// Deprecated: use `--proof-size` and let `ref_time` come from the
+        /// dry-run's `gas_required`. Kept only so existing scripts that pass
+        /// a single scalar gas limit keep working; ignored once `--proof-size`
+        /// is also supplied.
         #[arg(short, long, default_value = "500000000000")]
         gas_limit: u64,
 
+        /// `proof_size` component of the weight v2 limit. When set, the
+        /// `ref_time` half of the limit is taken from the dry run's
+        /// `gas_required` plus `gas_margin_percent`, instead of `--gas-limit`.
+        #[arg(long)]
+        proof_size: Option<u64>,
+
+        /// Safety margin applied to the dry run's `gas_required` weight
+        /// before submitting the real extrinsic, as a percentage.
+        #[arg(long, default_value = "20")]
+        gas_margin_percent: u64,
+
+        /// Storage deposit limit for the instantiation, in plancks. When
+        /// omitted, the dry run's computed `storage_deposit` is used.
+        #[arg(long)]
+        storage_deposit_limit: Option<u128>,
+
         /// Account to use for instantiation
         #[arg(short = 'a', long, default_value = "alice")]
         account: String,
@@ -75,14 +125,196 @@ enum Commands {
         #[arg(short, long)]
         address: String,
 
-        /// Message selector (hex)
+        /// Message selector (hex). Ignored when `--metadata` and `--message`
+        /// are both given.
         #[arg(short, long)]
         selector: String,
 
-        /// Message arguments (hex)
+        /// Message arguments (hex). Ignored when `--metadata` and `--message`
+        /// are both given.
         #[arg(short = 'r', long, default_value = "")]
         args: String,
+
+        /// Path to the contract's `.contract`/metadata `.json` bundle. When
+        /// set together with `--message`, the selector and arguments are
+        /// resolved from the ABI by name instead of `--selector`/`--args`.
+        #[arg(long)]
+        metadata: Option<PathBuf>,
+
+        /// Message name to look up in `--metadata` (e.g. `evaluate_access`).
+        #[arg(long)]
+        message: Option<String>,
+
+        /// A message argument as `label=value`, repeatable. Only used
+        /// together with `--metadata`/`--message`.
+        #[arg(long = "arg")]
+        metadata_args: Vec<String>,
+    },
+
+    /// Subscribe to a contract's events on the finalized chain
+    Watch {
+        /// Contract address to watch
+        #[arg(short, long)]
+        address: String,
+
+        /// Path to the contract's `.contract`/metadata `.json` bundle, used
+        /// to decode event variant indices into labels and fields.
+        #[arg(short, long)]
+        metadata: PathBuf,
+
+        /// Block number to backfill events from before following the
+        /// finalized chain head, so a restarted watcher doesn't miss events
+        /// emitted while it was down.
+        #[arg(long)]
+        from_block: Option<u32>,
+
+        /// Optional webhook URL to POST each decoded event to as JSON, in
+        /// addition to logging it.
+        #[arg(long)]
+        webhook: Option<String>,
+    },
+
+    /// Upgrade a deployed contract's code in place: upload the new Wasm,
+    /// then dispatch the contract's `set_code_hash` message so it adopts it
+    /// while keeping its existing storage.
+    Upgrade {
+        /// Contract address to upgrade
+        #[arg(short, long)]
+        address: String,
+
+        /// Path to the new contract .wasm file to upload as the replacement
+        /// code
+        #[arg(short, long)]
+        wasm: PathBuf,
+
+        /// Path to the contract's `.contract`/metadata `.json` bundle, used
+        /// to resolve the `set_code_hash` message selector and encode its
+        /// `new_code_hash` argument.
+        #[arg(short, long)]
+        metadata: PathBuf,
+
+        /// `proof_size` component of the weight v2 limit, used as given
+        /// instead of the dry run's own `proof_size` estimate. The
+        /// `ref_time` half of the limit always comes from the dry run's
+        /// `gas_required` plus `gas_margin_percent`.
+        #[arg(long)]
+        proof_size: Option<u64>,
+
+        /// Safety margin applied to the dry run's `gas_required` weight
+        /// before submitting the real extrinsic, as a percentage.
+        #[arg(long, default_value = "20")]
+        gas_margin_percent: u64,
+
+        /// Storage deposit limit for the `set_code_hash` call, in plancks.
+        /// When omitted, the dry run's computed `storage_deposit` is used.
+        #[arg(long)]
+        storage_deposit_limit: Option<u128>,
+
+        /// Account to use for the upload and the `set_code_hash` call
+        #[arg(short = 'a', long, default_value = "alice")]
+        account: String,
     },
+
+    /// Dry-run every message of every deployed contract to measure its
+    /// gas/storage-deposit cost, writing `gas_report.json` and logging a
+    /// diff against the previous run.
+    Bench {
+        /// Directory containing contract .wasm and .json files
+        #[arg(short, long, default_value = "./target/ink")]
+        contracts_dir: PathBuf,
+
+        /// Account to use for uploading, instantiating and calling
+        #[arg(short, long, default_value = "alice")]
+        account: String,
+    },
+}
+
+/// Parse `--arg label=value` entries into `(label, value)` pairs.
+fn parse_metadata_args(raw: &[String]) -> Result<Vec<(String, String)>> {
+    raw.iter()
+        .map(|entry| {
+            let (label, value) = entry
+                .split_once('=')
+                .with_context(|| format!("Expected '--arg label=value', got '{}'", entry))?;
+            Ok((label.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Resolve the raw call data for a constructor/message, preferring
+/// metadata-driven resolution over a raw hex selector/args pair when a
+/// metadata path and name are supplied.
+fn resolve_call_data(
+    kind: metadata::MessageKind,
+    metadata_path: Option<&PathBuf>,
+    name: Option<&str>,
+    metadata_args: &[String],
+    selector: &str,
+    args: &str,
+) -> Result<Vec<u8>> {
+    match (metadata_path, name) {
+        (Some(path), Some(name)) => {
+            let bundle = metadata::ContractMetadata::load(path)?;
+            let parsed_args = parse_metadata_args(metadata_args)?;
+            bundle.encode_call(kind, name, &parsed_args)
+        }
+        _ => build_call_data(selector, args),
+    }
+}
+
+/// Mirrors `pallet_contracts_primitives::StorageDeposit`'s two variants as
+/// returned by the `ContractsApi_instantiate`/`ContractsApi_call` runtime
+/// APIs.
+#[derive(Debug, Decode)]
+enum StorageDeposit {
+    Refund(u128),
+    Charge(u128),
+}
+
+impl StorageDeposit {
+    /// The deposit amount regardless of refund/charge direction, suitable
+    /// for use as a `storage_deposit_limit` on the real extrinsic.
+    fn amount(&self) -> u128 {
+        match self {
+            StorageDeposit::Refund(amount) | StorageDeposit::Charge(amount) => *amount,
+        }
+    }
+}
+
+/// Mirrors the runtime's weight v2 type: an explicit `ref_time` (computation)
+/// and `proof_size` (PoV) component, as used by `gas_required`/`gas_consumed`
+/// in the dry-run result and by the real extrinsic's gas limit argument.
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+struct Weight {
+    ref_time: u64,
+    proof_size: u64,
+}
+
+impl Weight {
+    /// Apply a safety margin (as a percentage) on top of a dry run's
+    /// `gas_required`, since on-chain execution can consume slightly more
+    /// than the dry run measured against current storage state.
+    fn with_margin_percent(self, margin_percent: u64) -> Self {
+        Self {
+            ref_time: self.ref_time.saturating_mul(100 + margin_percent) / 100,
+            proof_size: self.proof_size.saturating_mul(100 + margin_percent) / 100,
+        }
+    }
+}
+
+/// Minimal mirror of `pallet_contracts_primitives::ContractResult<Result<...>,
+/// Balance>`, decoded from the raw SCALE bytes returned by
+/// `ContractsApi_instantiate`/`ContractsApi_call`. Only the fields the
+/// deployer needs (weight + deposit estimation) are modeled; the inner
+/// `result` is left as raw bytes since its success payload differs between
+/// `instantiate` (account id + result data) and `call` (result data only).
+#[derive(Debug, Decode)]
+struct DryRunResult {
+    gas_consumed: Weight,
+    gas_required: Weight,
+    storage_deposit: StorageDeposit,
+    debug_message: Vec<u8>,
+    result: Vec<u8>,
 }
 
 #[tokio::main]
@@ -105,19 +337,45 @@ async fn main() -> Result<()> {
     info!("Connected successfully!");
 
     match cli.command {
-        Commands::Upload { wasm, account } => {
-            upload_contract(&api, wasm, &account).await?;
+        Commands::Upload {
+            wasm,
+            account,
+            storage_deposit_limit,
+        } => {
+            upload_contract(&api, wasm, &account, storage_deposit_limit).await?;
         }
         Commands::Instantiate {
             code_hash,
             selector,
             args,
+            metadata,
+            constructor,
+            metadata_args,
             value,
             gas_limit,
+            proof_size,
+            gas_margin_percent,
+            storage_deposit_limit,
             account,
         } => {
+            let call_data = resolve_call_data(
+                metadata::MessageKind::Constructor,
+                metadata.as_ref(),
+                constructor.as_deref(),
+                &metadata_args,
+                &selector,
+                &args,
+            )?;
             instantiate_contract(
-                &api, &code_hash, &selector, &args, value, gas_limit, &account,
+                &api,
+                &code_hash,
+                &call_data,
+                value,
+                gas_limit,
+                proof_size,
+                gas_margin_percent,
+                storage_deposit_limit,
+                &account,
             )
             .await?;
         }
@@ -131,8 +389,54 @@ async fn main() -> Result<()> {
             address,
             selector,
             args,
+            metadata,
+            message,
+            metadata_args,
+        } => {
+            let call_data = resolve_call_data(
+                metadata::MessageKind::Message,
+                metadata.as_ref(),
+                message.as_deref(),
+                &metadata_args,
+                &selector,
+                &args,
+            )?;
+            query_contract(&api, &address, &call_data).await?;
+        }
+        Commands::Watch {
+            address,
+            metadata,
+            from_block,
+            webhook,
+        } => {
+            watch_contract(&api, &address, &metadata, from_block, webhook.as_deref()).await?;
+        }
+        Commands::Upgrade {
+            address,
+            wasm,
+            metadata,
+            proof_size,
+            gas_margin_percent,
+            storage_deposit_limit,
+            account,
+        } => {
+            upgrade_contract(
+                &api,
+                &address,
+                wasm,
+                &metadata,
+                proof_size,
+                gas_margin_percent,
+                storage_deposit_limit,
+                &account,
+            )
+            .await?;
+        }
+        Commands::Bench {
+            contracts_dir,
+            account,
         } => {
-            query_contract(&api, &address, &selector, &args).await?;
+            run_bench(&api, contracts_dir, &account).await?;
         }
     }
 
@@ -143,45 +447,142 @@ async fn upload_contract(
     api: &OnlineClient<PolkadotConfig>,
     wasm_path: PathBuf,
     account_name: &str,
-) -> Result<()> {
+    storage_deposit_limit: Option<u128>,
+) -> Result<H256> {
     info!("Uploading contract from {:?}", wasm_path);
 
-    let _signer = get_signer(account_name)?;
-    let _wasm = std::fs::read(&wasm_path).context("Failed to read WASM file")?;
+    let signer = DeploySigner::new(get_signer(account_name)?);
+    let wasm = std::fs::read(&wasm_path).context("Failed to read WASM file")?;
 
-    // Note: Full implementation requires subxt-based contract pallet integration
-    // The contracts pallet extrinsic needs to be constructed using the runtime metadata
-    // For production, use cargo-contract CLI which provides complete upload functionality
-    // Example: cargo contract upload --suri //Alice target/ink/contract.wasm
-    warn!("Contract upload not yet implemented - use cargo-contract CLI for deployment");
-    info!("To upload contract: cargo contract upload --suri //{} {:?}",
-        account_name.to_uppercase(), wasm_path);
+    // `Contracts::upload_code(code, storage_deposit_limit, determinism)`.
+    // `determinism: Enforced` matches cargo-contract's default and is
+    // required for code that may be `instantiate`d from another contract.
+    let upload_call = subxt::dynamic::tx(
+        "Contracts",
+        "upload_code",
+        vec![
+            Value::from_bytes(&wasm),
+            storage_deposit_limit
+                .map(|limit| Value::unnamed_variant("Some", vec![Value::u128(limit)]))
+                .unwrap_or_else(|| Value::unnamed_variant("None", vec![])),
+            Value::unnamed_variant("Enforced", vec![]),
+        ],
+    );
 
-    Ok(())
+    let events = api
+        .tx()
+        .sign_and_submit_then_watch_default(&upload_call, &signer)
+        .await
+        .context("Failed to submit upload_code extrinsic")?
+        .wait_for_finalized_success()
+        .await
+        .context("upload_code extrinsic failed")?;
+
+    let code_stored = events
+        .find_first::<subxt::events::EventDetails<PolkadotConfig>>()
+        .ok();
+    let _ = code_stored;
+
+    // The code hash is the Blake2-256 of the uploaded WASM, matching what
+    // `pallet_contracts::CodeStored` reports; computing it locally avoids
+    // depending on decoding the dynamic event field layout.
+    let code_hash = sp_core::blake2_256(&wasm);
+    let code_hash = H256::from(code_hash);
+
+    info!("Contract uploaded, code hash: {:?}", code_hash);
+    Ok(code_hash)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn instantiate_contract(
     api: &OnlineClient<PolkadotConfig>,
-    _code_hash: &str,
-    _selector: &str,
-    _args: &str,
-    _value: u128,
-    _gas_limit: u64,
+    code_hash: &str,
+    call_data: &[u8],
+    value: u128,
+    gas_limit: u64,
+    proof_size: Option<u64>,
+    gas_margin_percent: u64,
+    storage_deposit_limit_arg: Option<u128>,
     account_name: &str,
-) -> Result<()> {
+) -> Result<H256> {
     info!("Instantiating contract with account {}", account_name);
 
-    let _signer = get_signer(account_name)?;
+    let signer = DeploySigner::new(get_signer(account_name)?);
+    let code_hash = parse_h256(code_hash)?;
 
-    // Note: Full implementation requires constructing contracts::instantiate extrinsic
-    // This needs runtime metadata and proper gas weight estimation
-    // For production, use cargo-contract CLI which handles all contract operations
-    // Example: cargo contract instantiate --suri //Alice --constructor new
-    warn!("Contract instantiation not yet implemented - use cargo-contract CLI");
-    info!("To instantiate contract: cargo contract instantiate --suri //{} --constructor new",
-        account_name.to_uppercase());
+    let dry_run = dry_run_instantiate(api, &signer, code_hash, value, call_data).await?;
 
-    Ok(())
+    if !dry_run.debug_message.is_empty() {
+        info!(
+            "Dry run debug message: {}",
+            String::from_utf8_lossy(&dry_run.debug_message)
+        );
+    }
+    info!(
+        "Dry run estimate: gas_required={:?}, storage_deposit={:?}",
+        dry_run.gas_required, dry_run.storage_deposit
+    );
+
+    let weight_limit = if let Some(proof_size) = proof_size {
+        Weight {
+            ref_time: dry_run.gas_required.with_margin_percent(gas_margin_percent).ref_time,
+            proof_size,
+        }
+    } else {
+        warn!(
+            "--proof-size not set, falling back to the deprecated scalar --gas-limit ({})",
+            gas_limit
+        );
+        Weight {
+            ref_time: gas_limit,
+            proof_size: dry_run.gas_required.with_margin_percent(gas_margin_percent).proof_size,
+        }
+    };
+
+    let storage_deposit_limit = storage_deposit_limit_arg.unwrap_or_else(|| dry_run.storage_deposit.amount());
+
+    let instantiate_call = subxt::dynamic::tx(
+        "Contracts",
+        "instantiate",
+        vec![
+            Value::u128(value),
+            Value::named_composite(vec![
+                ("ref_time", Value::u128(u128::from(weight_limit.ref_time))),
+                ("proof_size", Value::u128(u128::from(weight_limit.proof_size))),
+            ]),
+            Value::unnamed_variant("Some", vec![Value::u128(storage_deposit_limit)]),
+            Value::from_bytes(code_hash.as_bytes()),
+            Value::from_bytes(call_data),
+            Value::from_bytes(&[]),
+        ],
+    );
+
+    let events = api
+        .tx()
+        .sign_and_submit_then_watch_default(&instantiate_call, &signer)
+        .await
+        .context("Failed to submit instantiate extrinsic")?
+        .wait_for_finalized_success()
+        .await
+        .context("instantiate extrinsic failed")?;
+
+    let contract_address = events
+        .iter()
+        .filter_map(|event| event.ok())
+        .find(|event| event.pallet_name() == "Contracts" && event.variant_name() == "Instantiated")
+        .map(|event| {
+            // `Instantiated { deployer: AccountId, contract: AccountId }`,
+            // encoded in field declaration order.
+            let mut field_bytes = event.field_bytes();
+            let _deployer = H256::decode(&mut field_bytes)
+                .context("Failed to decode Instantiated event deployer")?;
+            H256::decode(&mut field_bytes).context("Failed to decode Instantiated event contract")
+        })
+        .transpose()?
+        .context("No Instantiated event found in the instantiate extrinsic's result")?;
+
+    info!("Contract instantiated successfully at {:?}", contract_address);
+    Ok(contract_address)
 }
 
 async fn deploy_all_contracts(
@@ -200,16 +601,42 @@ async fn deploy_all_contracts(
 
     for contract_name in contract_names {
         let wasm_path = contracts_dir.join(format!("{}.wasm", contract_name));
+        let metadata_path = contracts_dir.join(format!("{}.json", contract_name));
 
-        if wasm_path.exists() {
-            info!("Deploying {}", contract_name);
-            upload_contract(api, wasm_path, account_name).await?;
-        } else {
+        if !wasm_path.exists() {
             warn!(
                 "Contract {} not found at {:?}",
                 contract_name, wasm_path
             );
+            continue;
         }
+
+        info!("Deploying {}", contract_name);
+        let code_hash = upload_contract(api, wasm_path, account_name, None).await?;
+
+        if !metadata_path.exists() {
+            warn!(
+                "Metadata for {} not found at {:?}, skipping instantiation",
+                contract_name, metadata_path
+            );
+            continue;
+        }
+
+        let bundle = metadata::ContractMetadata::load(&metadata_path)?;
+        let call_data = bundle.encode_call(metadata::MessageKind::Constructor, "new", &[])?;
+
+        instantiate_contract(
+            api,
+            &format!("0x{}", hex::encode(code_hash.as_bytes())),
+            &call_data,
+            0,
+            500_000_000_000,
+            None,
+            20,
+            None,
+            account_name,
+        )
+        .await?;
     }
 
     Ok(())
@@ -217,23 +644,539 @@ async fn deploy_all_contracts(
 
 async fn query_contract(
     api: &OnlineClient<PolkadotConfig>,
-    _address: &str,
-    _selector: &str,
-    _args: &str,
+    address: &str,
+    call_data: &[u8],
 ) -> Result<()> {
-    info!("Querying contract");
+    info!("Querying contract at {}", address);
+
+    let dest = parse_h256(address)?;
+    let signer = DeploySigner::new(get_signer("alice")?);
+
+    let dry_run = dry_run_call(api, &signer, dest, &call_data).await?;
+
+    if !dry_run.debug_message.is_empty() {
+        info!(
+            "Query debug message: {}",
+            String::from_utf8_lossy(&dry_run.debug_message)
+        );
+    }
+
+    // The return data is not decoded against the contract's metadata here,
+    // so its concrete type is unknown and it's reported as raw SCALE bytes
+    // rather than a decoded value.
+    info!("Query result (raw SCALE bytes): 0x{}", hex::encode(&dry_run.result));
+    info!("Gas consumed: {:?}", dry_run.gas_consumed);
+
+    Ok(())
+}
+
+/// Upload `wasm` as the replacement code for the contract at `address`, then
+/// dispatch its `set_code_hash` message so it adopts the new code in place,
+/// preserving whatever storage the old code left behind.
+#[allow(clippy::too_many_arguments)]
+async fn upgrade_contract(
+    api: &OnlineClient<PolkadotConfig>,
+    address: &str,
+    wasm: PathBuf,
+    metadata_path: &PathBuf,
+    proof_size: Option<u64>,
+    gas_margin_percent: u64,
+    storage_deposit_limit: Option<u128>,
+    account_name: &str,
+) -> Result<()> {
+    info!("Uploading replacement code for {}", address);
+    let new_code_hash = upload_contract(api, wasm, account_name, None).await?;
+
+    let bundle = metadata::ContractMetadata::load(metadata_path)?;
+    let call_data = bundle.encode_call(
+        metadata::MessageKind::Message,
+        "set_code_hash",
+        &[(
+            "new_code_hash".to_string(),
+            format!("0x{}", hex::encode(new_code_hash.as_bytes())),
+        )],
+    )?;
 
-    // Note: Contract queries require constructing contract call RPC requests
-    // The implementation needs contract ABI metadata and proper encoding
-    // For production, use cargo-contract CLI or Polkadot.js for contract interaction
-    // Example: cargo contract call --contract <addr> --message <selector>
-    warn!("Contract query not yet implemented - use cargo-contract CLI or Polkadot.js");
-    info!("To query contract: cargo contract call --contract {} --message {}",
-        _address, _selector);
+    call_contract(
+        api,
+        address,
+        &call_data,
+        proof_size,
+        gas_margin_percent,
+        storage_deposit_limit,
+        account_name,
+    )
+    .await?;
 
+    info!("Contract {} upgraded to code hash {:?}", address, new_code_hash);
     Ok(())
 }
 
+/// Dry-run a `call_data` message against `dest` to estimate its weight and
+/// storage deposit, then submit it as a real `Contracts::call` extrinsic.
+/// Shared by any command that needs to dispatch a message rather than just
+/// read its return value (currently only `upgrade`'s `set_code_hash` call).
+async fn call_contract(
+    api: &OnlineClient<PolkadotConfig>,
+    address: &str,
+    call_data: &[u8],
+    proof_size: Option<u64>,
+    gas_margin_percent: u64,
+    storage_deposit_limit_arg: Option<u128>,
+    account_name: &str,
+) -> Result<()> {
+    let signer = DeploySigner::new(get_signer(account_name)?);
+    let dest = parse_h256(address)?;
+
+    let dry_run = dry_run_call(api, &signer, dest, call_data).await?;
+
+    if !dry_run.debug_message.is_empty() {
+        info!(
+            "Dry run debug message: {}",
+            String::from_utf8_lossy(&dry_run.debug_message)
+        );
+    }
+    info!(
+        "Dry run estimate: gas_required={:?}, storage_deposit={:?}",
+        dry_run.gas_required, dry_run.storage_deposit
+    );
+
+    let weight_limit = match proof_size {
+        Some(proof_size) => Weight {
+            ref_time: dry_run.gas_required.with_margin_percent(gas_margin_percent).ref_time,
+            proof_size,
+        },
+        None => dry_run.gas_required.with_margin_percent(gas_margin_percent),
+    };
+
+    let storage_deposit_limit =
+        storage_deposit_limit_arg.unwrap_or_else(|| dry_run.storage_deposit.amount());
+
+    let call = subxt::dynamic::tx(
+        "Contracts",
+        "call",
+        vec![
+            Value::from_bytes(dest.as_bytes()),
+            Value::u128(0),
+            Value::named_composite(vec![
+                ("ref_time", Value::u128(u128::from(weight_limit.ref_time))),
+                ("proof_size", Value::u128(u128::from(weight_limit.proof_size))),
+            ]),
+            Value::unnamed_variant("Some", vec![Value::u128(storage_deposit_limit)]),
+            Value::from_bytes(call_data),
+        ],
+    );
+
+    api.tx()
+        .sign_and_submit_then_watch_default(&call, &signer)
+        .await
+        .context("Failed to submit call extrinsic")?
+        .wait_for_finalized_success()
+        .await
+        .context("call extrinsic failed")?;
+
+    info!("Call dispatched successfully");
+    Ok(())
+}
+
+/// Upload, instantiate and dry-run every message of every known contract
+/// found in `contracts_dir`, writing the measurements to `gas_report.json`
+/// and logging a diff against whatever report was already there.
+async fn run_bench(
+    api: &OnlineClient<PolkadotConfig>,
+    contracts_dir: PathBuf,
+    account_name: &str,
+) -> Result<()> {
+    let contract_names = ["access_registry", "attribute_store", "policy_engine", "payment_integration"];
+    let mut report = bench::Report::default();
+
+    for contract_name in contract_names {
+        let wasm_path = contracts_dir.join(format!("{}.wasm", contract_name));
+        let metadata_path = contracts_dir.join(format!("{}.json", contract_name));
+
+        if !wasm_path.exists() || !metadata_path.exists() {
+            warn!(
+                "Skipping bench for {}: wasm or metadata not found in {:?}",
+                contract_name, contracts_dir
+            );
+            continue;
+        }
+
+        info!("Benchmarking {}", contract_name);
+        let contract_report =
+            bench_contract(api, contract_name, wasm_path, metadata_path, account_name).await?;
+        report.contracts.push(contract_report);
+    }
+
+    let report_path = contracts_dir.join("gas_report.json");
+    let previous = bench::load_report(&report_path)?;
+    bench::print_diff(previous.as_ref(), &report);
+    bench::write_report(&report_path, &report)?;
+
+    info!("Gas report written to {:?}", report_path);
+    Ok(())
+}
+
+/// Deploy a fresh instance of one contract, then dry-run a sample call for
+/// each of its messages, recording `gas_consumed` and `storage_deposit`.
+async fn bench_contract(
+    api: &OnlineClient<PolkadotConfig>,
+    contract_name: &str,
+    wasm_path: PathBuf,
+    metadata_path: PathBuf,
+    account_name: &str,
+) -> Result<bench::ContractReport> {
+    let code_hash = upload_contract(api, wasm_path, account_name, None).await?;
+    let bundle = metadata::ContractMetadata::load(&metadata_path)?;
+    let ctor_data = bundle.encode_call(metadata::MessageKind::Constructor, "new", &[])?;
+
+    let address = instantiate_contract(
+        api,
+        &format!("0x{}", hex::encode(code_hash.as_bytes())),
+        &ctor_data,
+        0,
+        500_000_000_000,
+        None,
+        20,
+        None,
+        account_name,
+    )
+    .await?;
+
+    // `policy_engine`'s `create_policy`/`update_policy` take a
+    // `Vec<(String, String)>` argument, which the generic
+    // `--arg label=value` encoder can't express, so they and
+    // `evaluate_access` are sampled at their documented
+    // `MAX_ATTRIBUTES`/`MAX_STRING_LENGTH` worst case by
+    // `policy_engine_bench_samples` instead of going through
+    // `encode_sample_call`. Every other message is sampled generically.
+    let hand_sampled: &[&str] = if contract_name == "policy_engine" {
+        &["create_policy", "update_policy", "evaluate_access"]
+    } else {
+        &[]
+    };
+    let mut samples = Vec::new();
+    for name in bundle.message_names() {
+        if hand_sampled.contains(&name) {
+            continue;
+        }
+        match bundle.encode_sample_call(metadata::MessageKind::Message, name) {
+            Ok(call_data) => samples.push((name.to_string(), call_data)),
+            Err(err) => warn!(
+                "Skipping {}::{} in bench, unsupported argument type: {}",
+                contract_name, name, err
+            ),
+        }
+    }
+    samples.sort_by(|a, b| a.0.cmp(&b.0));
+    if contract_name == "policy_engine" {
+        samples.extend(policy_engine_bench_samples(&bundle)?);
+    }
+
+    let signer = DeploySigner::new(get_signer(account_name)?);
+    let mut entries = Vec::with_capacity(samples.len());
+    for (label, call_data) in samples {
+        let dry_run = dry_run_call(api, &signer, address, &call_data).await?;
+        entries.push(bench::GasEntry {
+            label,
+            ref_time: dry_run.gas_consumed.ref_time,
+            proof_size: dry_run.gas_consumed.proof_size,
+            storage_deposit: dry_run.storage_deposit.amount(),
+        });
+    }
+
+    Ok(bench::ContractReport {
+        contract: contract_name.to_string(),
+        entries,
+    })
+}
+
+/// Hand-rolled worst-case call data for `policy_engine`'s attribute-bearing
+/// messages, filling `required_attributes` to `MAX_ATTRIBUTES` entries of
+/// `MAX_STRING_LENGTH` bytes each so the recorded weight reflects the
+/// contract's documented upper bound rather than an empty-input best case.
+fn policy_engine_bench_samples(bundle: &metadata::ContractMetadata) -> Result<Vec<(String, Vec<u8>)>> {
+    const MAX_STRING_LENGTH: usize = 256;
+    const MAX_ATTRIBUTES: usize = 50;
+
+    let max_string = "a".repeat(MAX_STRING_LENGTH);
+    let max_attributes: Vec<(String, String)> = (0..MAX_ATTRIBUTES)
+        .map(|_| (max_string.clone(), max_string.clone()))
+        .collect();
+
+    let mut create_policy = bundle
+        .selector(metadata::MessageKind::Message, "create_policy")?
+        .to_vec();
+    max_string.encode_to(&mut create_policy);
+    max_attributes.encode_to(&mut create_policy);
+    1u8.encode_to(&mut create_policy);
+
+    let mut update_policy = bundle
+        .selector(metadata::MessageKind::Message, "update_policy")?
+        .to_vec();
+    0u32.encode_to(&mut update_policy);
+    max_attributes.encode_to(&mut update_policy);
+    1u8.encode_to(&mut update_policy);
+    true.encode_to(&mut update_policy);
+
+    let mut evaluate_access = bundle
+        .selector(metadata::MessageKind::Message, "evaluate_access")?
+        .to_vec();
+    [0u8; 32].encode_to(&mut evaluate_access);
+    0u32.encode_to(&mut evaluate_access);
+
+    Ok(vec![
+        (
+            "create_policy (max attributes/string length)".to_string(),
+            create_policy,
+        ),
+        (
+            "update_policy (max attributes/string length)".to_string(),
+            update_policy,
+        ),
+        ("evaluate_access".to_string(), evaluate_access),
+    ])
+}
+
+/// Delay between `subscribe_finalized` reconnect attempts, so a
+/// persistently-unreachable node backs off instead of busy-looping.
+const RECONNECT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Subscribe to finalized blocks and decode `Contracts::ContractEmitted`
+/// events for one contract, using its metadata to resolve event labels and
+/// field values. Optionally backfills from `from_block` first, and keeps
+/// watching across dropped subscriptions so a long-lived operator process
+/// doesn't need babysitting.
+async fn watch_contract(
+    api: &OnlineClient<PolkadotConfig>,
+    address: &str,
+    metadata_path: &PathBuf,
+    from_block: Option<u32>,
+    webhook: Option<&str>,
+) -> Result<()> {
+    let contract = parse_h256(address)?;
+    let bundle = metadata::ContractMetadata::load(metadata_path)?;
+
+    if let Some(from_block) = from_block {
+        info!("Backfilling contract events from block {}", from_block);
+        backfill_contract_events(api, contract, &bundle, from_block, webhook).await?;
+    }
+
+    info!("Watching contract {} for finalized events", address);
+
+    loop {
+        let mut blocks_sub = match api.blocks().subscribe_finalized().await {
+            Ok(sub) => sub,
+            Err(err) => {
+                warn!("Failed to subscribe to finalized blocks, retrying: {}", err);
+                tokio::time::sleep(RECONNECT_BACKOFF).await;
+                continue;
+            }
+        };
+
+        while let Some(block) = blocks_sub.next().await {
+            match block {
+                Ok(block) => {
+                    if let Err(err) =
+                        process_block_events(&block, contract, &bundle, webhook).await
+                    {
+                        warn!("Failed to process events for block {}: {}", block.number(), err);
+                    }
+                }
+                Err(err) => {
+                    warn!("Lost finalized block subscription, reconnecting: {}", err);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Re-fetch every finalized block from `from_block` up to (but not
+/// including) the current finalized head, decoding contract events in the
+/// same way the live subscription does.
+async fn backfill_contract_events(
+    api: &OnlineClient<PolkadotConfig>,
+    contract: H256,
+    bundle: &metadata::ContractMetadata,
+    from_block: u32,
+    webhook: Option<&str>,
+) -> Result<()> {
+    let finalized_head = api
+        .backend()
+        .latest_finalized_block_ref()
+        .await
+        .context("Failed to fetch the latest finalized block reference")?;
+    let latest_block = api
+        .blocks()
+        .at(finalized_head)
+        .await
+        .context("Failed to fetch the latest finalized block")?;
+    let latest_number = latest_block.number();
+
+    for number in from_block..latest_number {
+        let block_hash = api
+            .backend()
+            .block_hash(number.into())
+            .await
+            .context("Failed to resolve block hash for backfill")?
+            .with_context(|| format!("No block found at height {}", number))?;
+        let block = api
+            .blocks()
+            .at(block_hash)
+            .await
+            .with_context(|| format!("Failed to fetch block {} for backfill", number))?;
+
+        process_block_events(&block, contract, bundle, webhook).await?;
+    }
+
+    Ok(())
+}
+
+/// Scan one block's events for `Contracts::ContractEmitted` entries
+/// belonging to `contract`, decode each via `bundle`, and emit it.
+async fn process_block_events(
+    block: &subxt::blocks::Block<PolkadotConfig, OnlineClient<PolkadotConfig>>,
+    contract: H256,
+    bundle: &metadata::ContractMetadata,
+    webhook: Option<&str>,
+) -> Result<()> {
+    let events = block
+        .events()
+        .await
+        .context("Failed to fetch block events")?;
+
+    for event in events.iter() {
+        let event = event.context("Failed to decode an event record from the block")?;
+        if event.pallet_name() != "Contracts" || event.variant_name() != "ContractEmitted" {
+            continue;
+        }
+
+        // `ContractEmitted { contract: AccountId, data: Vec<u8> }`, encoded
+        // in field declaration order.
+        let mut field_bytes = event.field_bytes();
+        let emitter =
+            H256::decode(&mut field_bytes).context("Failed to decode event emitter address")?;
+        if emitter != contract {
+            continue;
+        }
+        let data =
+            Vec::<u8>::decode(&mut field_bytes).context("Failed to decode event payload")?;
+
+        match bundle.decode_event(&data) {
+            Ok(decoded) => emit_decoded_event(block.number(), &decoded, webhook).await,
+            Err(err) => warn!("Failed to decode contract event: {}", err),
+        }
+    }
+
+    Ok(())
+}
+
+/// Log a decoded event and, if configured, POST it to a webhook as JSON.
+async fn emit_decoded_event(
+    block_number: u32,
+    decoded: &metadata::DecodedEvent,
+    webhook: Option<&str>,
+) {
+    info!(
+        "[block {}] {} {:?}",
+        block_number, decoded.label, decoded.fields
+    );
+
+    if let Some(url) = webhook {
+        let payload = serde_json::json!({
+            "block": block_number,
+            "event": decoded.label,
+            "fields": decoded.fields,
+        });
+
+        if let Err(err) = reqwest::Client::new().post(url).json(&payload).send().await {
+            warn!("Failed to deliver event to webhook {}: {}", url, err);
+        }
+    }
+}
+
+/// Dry-run `ContractsApi_instantiate` via `state_call`, returning the
+/// decoded weight/deposit estimate used to size the real extrinsic.
+async fn dry_run_instantiate(
+    api: &OnlineClient<PolkadotConfig>,
+    signer: &DeploySigner,
+    code_hash: H256,
+    value: u128,
+    call_data: &[u8],
+) -> Result<DryRunResult> {
+    #[derive(Encode)]
+    enum Code {
+        #[allow(dead_code)]
+        Upload(Vec<u8>),
+        Existing(H256),
+    }
+
+    // `(origin, value, gas_limit: Option<Weight>, storage_deposit_limit:
+    // Option<Balance>, code, data, salt)`.
+    let mut params = Vec::new();
+    signer.account_id().encode_to(&mut params);
+    value.encode_to(&mut params);
+    Option::<Weight>::None.encode_to(&mut params);
+    Option::<u128>::None.encode_to(&mut params);
+    Code::Existing(code_hash).encode_to(&mut params);
+    call_data.to_vec().encode_to(&mut params);
+    Vec::<u8>::new().encode_to(&mut params);
+
+    let raw = api
+        .backend()
+        .call("ContractsApi_instantiate", Some(&params), None)
+        .await
+        .context("ContractsApi_instantiate state_call failed")?;
+
+    DryRunResult::decode(&mut raw.as_ref()).context("Failed to decode instantiate dry-run result")
+}
+
+/// Dry-run `ContractsApi_call` via `state_call`, used both by `query` (read
+/// a message's return value) and ahead of a real `call` extrinsic.
+async fn dry_run_call(
+    api: &OnlineClient<PolkadotConfig>,
+    signer: &DeploySigner,
+    dest: H256,
+    call_data: &[u8],
+) -> Result<DryRunResult> {
+    // `(origin, dest, value, gas_limit: Option<Weight>,
+    // storage_deposit_limit: Option<Balance>, input_data)`.
+    let mut params = Vec::new();
+    signer.account_id().encode_to(&mut params);
+    dest.encode_to(&mut params);
+    0u128.encode_to(&mut params);
+    Option::<Weight>::None.encode_to(&mut params);
+    Option::<u128>::None.encode_to(&mut params);
+    call_data.to_vec().encode_to(&mut params);
+
+    let raw = api
+        .backend()
+        .call("ContractsApi_call", Some(&params), None)
+        .await
+        .context("ContractsApi_call state_call failed")?;
+
+    DryRunResult::decode(&mut raw.as_ref()).context("Failed to decode call dry-run result")
+}
+
+/// Concatenate a hex-encoded 4-byte selector with hex-encoded SCALE-encoded
+/// arguments into the raw call data a contract message expects.
+fn build_call_data(selector: &str, args: &str) -> Result<Vec<u8>> {
+    let mut data = hex::decode(selector.trim_start_matches("0x")).context("Invalid selector hex")?;
+    if !args.is_empty() {
+        data.extend(hex::decode(args.trim_start_matches("0x")).context("Invalid args hex")?);
+    }
+    Ok(data)
+}
+
+fn parse_h256(input: &str) -> Result<H256> {
+    let bytes = hex::decode(input.trim_start_matches("0x")).context("Invalid hex value")?;
+    if bytes.len() != 32 {
+        anyhow::bail!("Expected a 32-byte hash, got {} bytes", bytes.len());
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
 fn get_signer(account_name: &str) -> Result<Pair> {
     let keyring = match account_name.to_lowercase().as_str() {
         "alice" => AccountKeyring::Alice,
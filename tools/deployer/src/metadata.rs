@@ -0,0 +1,305 @@
+//! Loads an ink! `.contract`/`.json` bundle and resolves constructors and
+//! messages by their human-readable name instead of requiring callers to
+//! pass raw hex selectors and pre-encoded SCALE arguments.
+
+use anyhow::{Context, Result};
+use scale::{Decode, Encode};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Which half of the contract's ABI a name should be looked up in.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Constructor,
+    Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Bundle {
+    spec: Spec,
+}
+
+#[derive(Debug, Deserialize)]
+struct Spec {
+    constructors: Vec<MessageSpec>,
+    messages: Vec<MessageSpec>,
+    #[serde(default)]
+    events: Vec<EventSpec>,
+}
+
+/// An `#[ink(event)]` declaration, in the order the contract declares it.
+/// ink! encodes an emitted event as `[variant_index as u8] || fields...`,
+/// where `variant_index` is this event's position in `spec.events` — so,
+/// unlike constructors/messages, events are looked up by index, not by
+/// decoding a selector.
+#[derive(Debug, Deserialize)]
+struct EventSpec {
+    label: String,
+    #[serde(default)]
+    args: Vec<ArgSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageSpec {
+    label: String,
+    selector: String,
+    #[serde(default)]
+    args: Vec<ArgSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArgSpec {
+    label: String,
+    #[serde(rename = "type")]
+    ty: ArgType,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArgType {
+    #[serde(rename = "displayName")]
+    display_name: Vec<String>,
+}
+
+/// A decoded `#[ink(event)]` emission: the declared event label plus its
+/// fields, stringified in declaration order for display/logging purposes.
+#[derive(Debug, Clone)]
+pub struct DecodedEvent {
+    pub label: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/// A parsed ink! contract bundle, indexed by constructor/message name.
+pub struct ContractMetadata {
+    constructors: HashMap<String, MessageSpec>,
+    messages: HashMap<String, MessageSpec>,
+    events: Vec<EventSpec>,
+}
+
+impl ContractMetadata {
+    /// Load and parse a `.contract` or metadata `.json` file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read contract metadata at {:?}", path))?;
+        let bundle: Bundle =
+            serde_json::from_str(&raw).context("Failed to parse ink! contract metadata JSON")?;
+
+        let constructors = bundle
+            .spec
+            .constructors
+            .into_iter()
+            .map(|spec| (spec.label.clone(), spec))
+            .collect();
+        let messages = bundle
+            .spec
+            .messages
+            .into_iter()
+            .map(|spec| (spec.label.clone(), spec))
+            .collect();
+
+        Ok(Self {
+            constructors,
+            messages,
+            events: bundle.spec.events,
+        })
+    }
+
+    fn lookup(&self, kind: MessageKind, name: &str) -> Result<&MessageSpec> {
+        let table = match kind {
+            MessageKind::Constructor => &self.constructors,
+            MessageKind::Message => &self.messages,
+        };
+        table
+            .get(name)
+            .with_context(|| format!("No {:?} named '{}' in contract metadata", kind, name))
+    }
+
+    /// The 4-byte selector for a constructor or message, by name.
+    pub fn selector(&self, kind: MessageKind, name: &str) -> Result<[u8; 4]> {
+        let spec = self.lookup(kind, name)?;
+        let bytes = hex::decode(spec.selector.trim_start_matches("0x"))
+            .context("Contract metadata selector is not valid hex")?;
+        if bytes.len() != 4 {
+            anyhow::bail!(
+                "Selector for '{}' is {} bytes, expected 4",
+                name,
+                bytes.len()
+            );
+        }
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&bytes);
+        Ok(selector)
+    }
+
+    /// Resolve the selector for `name` and SCALE-encode `args` (`label=value`
+    /// pairs, in any order) according to the argument type signatures the
+    /// metadata declares, returning the full call data (`selector || args`)
+    /// ready to pass to `instantiate`/`call`.
+    pub fn encode_call(
+        &self,
+        kind: MessageKind,
+        name: &str,
+        args: &[(String, String)],
+    ) -> Result<Vec<u8>> {
+        let spec = self.lookup(kind, name)?;
+        let selector = self.selector(kind, name)?;
+
+        let mut data = selector.to_vec();
+        for arg_spec in &spec.args {
+            let value = args
+                .iter()
+                .find(|(label, _)| label == &arg_spec.label)
+                .map(|(_, value)| value.as_str())
+                .with_context(|| format!("Missing --arg for '{}'", arg_spec.label))?;
+            encode_typed_arg(&arg_spec.ty, value, &mut data)
+                .with_context(|| format!("Failed to encode arg '{}'", arg_spec.label))?;
+        }
+
+        Ok(data)
+    }
+
+    /// Names of every message declared in this contract's ABI, for callers
+    /// (like `bench`) that want to exercise all of them generically rather
+    /// than by name.
+    pub fn message_names(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(String::as_str)
+    }
+
+    /// Resolve `name`'s selector and SCALE-encode a zero-valued sample for
+    /// each of its arguments, for gas-benchmarking callers that only care
+    /// about exercising a message, not its specific argument values. Fails
+    /// the same way [`Self::encode_call`] does when an argument's type
+    /// isn't one [`encode_typed_arg`] supports.
+    pub fn encode_sample_call(&self, kind: MessageKind, name: &str) -> Result<Vec<u8>> {
+        let spec = self.lookup(kind, name)?;
+        let selector = self.selector(kind, name)?;
+
+        let mut data = selector.to_vec();
+        for arg_spec in &spec.args {
+            let value = sample_value_for(&arg_spec.ty);
+            encode_typed_arg(&arg_spec.ty, &value, &mut data)
+                .with_context(|| format!("Unsupported argument type for '{}'", arg_spec.label))?;
+        }
+
+        Ok(data)
+    }
+
+    /// Decode a `pallet_contracts::Event::ContractEmitted::data` payload
+    /// using this contract's event declarations, selecting the event by the
+    /// leading variant-index byte ink! prepends to every emitted event.
+    pub fn decode_event(&self, data: &[u8]) -> Result<DecodedEvent> {
+        let (&variant_index, mut rest) = data
+            .split_first()
+            .context("Empty event data, missing variant index byte")?;
+        let spec = self.events.get(variant_index as usize).with_context(|| {
+            format!(
+                "Event variant index {} not declared in contract metadata",
+                variant_index
+            )
+        })?;
+
+        let mut fields = Vec::with_capacity(spec.args.len());
+        for arg_spec in &spec.args {
+            let value = decode_typed_arg(&arg_spec.ty, &mut rest)
+                .with_context(|| format!("Failed to decode field '{}'", arg_spec.label))?;
+            fields.push((arg_spec.label.clone(), value));
+        }
+
+        Ok(DecodedEvent {
+            label: spec.label.clone(),
+            fields,
+        })
+    }
+}
+
+impl std::fmt::Debug for MessageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageKind::Constructor => write!(f, "constructor"),
+            MessageKind::Message => write!(f, "message"),
+        }
+    }
+}
+
+/// SCALE-encode a single `key=value` argument according to its ink! ABI
+/// `displayName`. Covers the primitive and common domain types used across
+/// this workspace's contracts (`Address`/`AccountId` hashes, integers,
+/// `bool`, `String`, and raw byte vectors); anything else is rejected rather
+/// than silently mis-encoded.
+fn encode_typed_arg(ty: &ArgType, value: &str, out: &mut Vec<u8>) -> Result<()> {
+    let name = ty.display_name.last().map(String::as_str).unwrap_or("");
+
+    match name {
+        "bool" => {
+            let v: bool = value.parse().context("Expected 'true' or 'false'")?;
+            v.encode_to(out);
+        }
+        "u8" => value.parse::<u8>()?.encode_to(out),
+        "u16" => value.parse::<u16>()?.encode_to(out),
+        "u32" => value.parse::<u32>()?.encode_to(out),
+        "u64" => value.parse::<u64>()?.encode_to(out),
+        "u128" => value.parse::<u128>()?.encode_to(out),
+        "String" | "str" => {
+            let s = value.to_string();
+            s.encode_to(out);
+        }
+        "Address" | "AccountId" | "H160" | "Hash" => {
+            let bytes = hex::decode(value.trim_start_matches("0x"))
+                .context("Expected a hex-encoded address")?;
+            out.extend_from_slice(&bytes);
+        }
+        "Vec" => {
+            let bytes =
+                hex::decode(value.trim_start_matches("0x")).context("Expected hex-encoded bytes")?;
+            bytes.encode_to(out);
+        }
+        other => anyhow::bail!("Unsupported argument type '{}'", other),
+    }
+
+    Ok(())
+}
+
+/// A zero-valued placeholder for `ty`, suitable for `encode_typed_arg`, used
+/// when a caller wants to exercise a message without caring about its
+/// argument values.
+fn sample_value_for(ty: &ArgType) -> String {
+    match ty.display_name.last().map(String::as_str).unwrap_or("") {
+        "bool" => "false".to_string(),
+        "H160" => format!("0x{}", "00".repeat(20)),
+        "Address" | "AccountId" | "Hash" => format!("0x{}", "00".repeat(32)),
+        "Vec" => "0x".to_string(),
+        _ => "0".to_string(),
+    }
+}
+
+/// The decoding counterpart to [`encode_typed_arg`]: consume a value of the
+/// given ABI type from the front of `input` and stringify it for display.
+fn decode_typed_arg(ty: &ArgType, input: &mut &[u8]) -> Result<String> {
+    let name = ty.display_name.last().map(String::as_str).unwrap_or("");
+
+    let value = match name {
+        "bool" => bool::decode(input)?.to_string(),
+        "u8" => u8::decode(input)?.to_string(),
+        "u16" => u16::decode(input)?.to_string(),
+        "u32" => u32::decode(input)?.to_string(),
+        "u64" => u64::decode(input)?.to_string(),
+        "u128" => u128::decode(input)?.to_string(),
+        "String" | "str" => String::decode(input)?,
+        "Address" | "AccountId" | "H160" | "Hash" => {
+            let len = if name == "H160" { 20 } else { 32 };
+            if input.len() < len {
+                anyhow::bail!("Expected {} address bytes, got {}", len, input.len());
+            }
+            let (bytes, rest) = input.split_at(len);
+            *input = rest;
+            format!("0x{}", hex::encode(bytes))
+        }
+        "Vec" => {
+            let bytes = Vec::<u8>::decode(input)?;
+            format!("0x{}", hex::encode(bytes))
+        }
+        other => anyhow::bail!("Unsupported argument type '{}'", other),
+    };
+
+    Ok(value)
+}